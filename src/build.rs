@@ -2,10 +2,17 @@
 //! the gettext workflow. `gettext` and its related commands like `xgettext` have to be in your path
 //! during build depending on which function you use.
 
-use std::io::{BufRead, BufReader};
+mod mo;
+mod po_file;
+mod preprocess;
+
+use crate::unescape_po_string;
+use std::collections::{BTreeSet, HashMap};
+use std::io::{self, BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::{env, ffi, fs};
+use thiserror::Error;
 use typed_builder::TypedBuilder;
 use walkdir::WalkDir;
 
@@ -21,6 +28,12 @@ pub struct XgettextArguments<'a> {
     #[builder(default = "C")]
     language: &'a str,
 
+    /// Name of the gettext domain, e.g. the crate name in a workspace with a catalog per crate.
+    /// If set, the POT file is named `<domain>.pot` and written to the directory of the output
+    /// file passed to [create_pot_file]. The domain is also used as package name unless
+    /// [Self::package_name] is set. See [update_domain_mo_files] for the matching PO files.
+    #[builder(default, setter(strip_option))]
+    domain: Option<&'a str>,
     /// Name of the package. Ignored if [Self::omit_header] is set.
     #[builder(default)]
     package_name: &'a str,
@@ -36,12 +49,20 @@ pub struct XgettextArguments<'a> {
     /// Omit output header lines. Might cause `xgettext` to omit non-ascii characters.
     #[builder(default)]
     omit_header: bool,
-    /// Sort strings alphabetically.
+    /// Sort strings alphabetically. Must not be combined with [Self::sort_by_file].
     #[builder(default)]
     sort_output: bool,
+    /// Sort strings by their source file and location. Must not be combined with
+    /// [Self::sort_output].
+    #[builder(default)]
+    sort_by_file: bool,
     /// Do not include the locations of source strings.
     #[builder(default)]
     no_location: bool,
+    /// How the locations of source strings are written, see [LocationMode]. Ignored if
+    /// [Self::no_location] is set. If `None` `xgettext` writes full locations.
+    #[builder(default, setter(strip_option))]
+    location_mode: Option<LocationMode>,
     /// Do not break long messages into several lines.
     #[builder(default)]
     no_wrap: bool,
@@ -52,50 +73,109 @@ pub struct XgettextArguments<'a> {
     #[builder(default)]
     no_creation_date: bool,
 
+    /// Comments for translators keyed by msgid. They are added as `#.` comments above the
+    /// matching msgids in the POT file after `xgettext` ran.
+    #[builder(default, setter(strip_option))]
+    extracted_comments: Option<HashMap<String, String>>,
+
+    /// Convert Rust raw string literals like `r#"..."#` to regular string literals before running
+    /// `xgettext`, which parses Rust as C and would otherwise misread them. Only relative paths
+    /// without `..` in [Self::input_files] are converted, a cargo warning is printed for others.
+    #[builder(default)]
+    rust_raw_strings: bool,
+    /// Convert doc comments (`///` or `//!`) starting with [Self::comment_key] to regular comments
+    /// and move them below following attributes before running `xgettext`, which would otherwise
+    /// not attach them to the msgid. Only relative paths without `..` in [Self::input_files] are
+    /// converted, a cargo warning is printed for others.
+    #[builder(default)]
+    rust_doc_comments: bool,
+
     /// Files which are searched for usage of `gettext`, `ngettext`, `pgettext` or `npgettext`.
-    /// If `None` [create_pot_file] defaults to all `.rs` files in `./src`.
+    /// If `None` [create_pot_file] defaults to all `.rs` files in [Self::input_dirs].
     #[builder(default, setter(strip_option))]
     input_files: Option<Vec<String>>,
+    /// Directories which are searched recursively for `.rs` files, e.g. `src` and `examples`.
+    /// Ignored if [Self::input_files] is set. If `None` [create_pot_file] defaults to `./src`.
+    #[builder(default, setter(strip_option))]
+    input_dirs: Option<Vec<String>>,
 }
 
-/// Creates a gettext POT file at `output_file` by calling `xgettext` with `args` arguments.
-pub fn create_pot_file(output_file: &str, args: XgettextArguments) {
-    let mut cmd = Command::new("xgettext");
-
-    // first add all used options to the command
-    cmd.arg(format!("--from-code={}", args.code_encoding))
-        .arg(format!("--language={}", args.language))
-        .arg(format!("--package-name={}", args.package_name))
-        .arg(format!("--package-version={}", args.package_version))
-        .arg(format!("--copyright-holder={}", args.copyright_holder))
-        .arg(format!("--output={}", output_file));
+/// The form of the `#:` location comments in a POT file, passed to `xgettext` as
+/// `--add-location`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LocationMode {
+    /// File names and line numbers, e.g. `#: src/main.rs:12`.
+    Full,
+    /// File names only, e.g. `#: src/main.rs`, which keeps the POT file stable when lines move.
+    File,
+    /// No locations at all.
+    Never,
+}
 
-    add_arg_if(&mut cmd, "--omit-header", args.omit_header);
-    add_arg_if(&mut cmd, "--sort-output", args.sort_output);
-    add_arg_if(&mut cmd, "--no-location", args.no_location);
-    add_arg_if(&mut cmd, "--no-wrap", args.no_wrap);
-    add_arg_if(&mut cmd, "--force-po", args.force_pot);
+impl LocationMode {
+    /// Returns the value of the `--add-location` option of `xgettext`.
+    fn as_arg(self) -> &'static str {
+        match self {
+            LocationMode::Full => "full",
+            LocationMode::File => "file",
+            LocationMode::Never => "never",
+        }
+    }
+}
 
-    if let Some(comment) = args.comment_key {
-        cmd.arg(format!("--add-comment={}", comment));
+/// Creates a gettext POT file at `output_file` by calling `xgettext` with `args` arguments.
+///
+/// The `xgettext` binary is looked up in `PATH` unless the `XGETTEXT` environment variable
+/// contains the path to use instead.
+///
+/// # Panics
+///
+/// Panics if both [XgettextArguments::sort_output] and [XgettextArguments::sort_by_file] are set.
+pub fn create_pot_file(output_file: &str, args: XgettextArguments) {
+    if args.sort_output && args.sort_by_file {
+        panic!("`sort_output` and `sort_by_file` of XgettextArguments are mutually exclusive");
     }
 
-    // If not files are given, get the paths to all `.rs` files in `src`.
-    let input_files = match args.input_files {
-        Some(files) => files,
-        None => WalkDir::new("./src")
-            .into_iter()
-            .filter_map(|entry| match entry {
-                Ok(entry) => Some(entry.path().to_string_lossy().to_string()),
-                Err(_) => None,
-            })
-            .filter(|path| path.ends_with(".rs"))
-            .collect(),
+    let output_file = &match args.domain {
+        Some(domain) => domain_pot_file(output_file, domain),
+        None => output_file.to_owned(),
+    };
+    let mut cmd = Command::new(xgettext_program());
+    add_options(&mut cmd, output_file, &args);
+
+    // If not files are given, get the paths to all `.rs` files in the input dirs or `src`.
+    let input_files = match (args.input_files, &args.input_dirs) {
+        (Some(files), _) => files,
+        (None, Some(dirs)) => dirs.iter().flat_map(rust_files).collect(),
+        (None, None) => rust_files("./src"),
     };
     cmd.args(&input_files);
 
+    // Let xgettext read preprocessed copies of the input files if necessary. The copies are
+    // removed when `_preprocessed` goes out of scope.
+    let doc_comment_key = args
+        .comment_key
+        .as_deref()
+        .filter(|_| args.rust_doc_comments);
+    let _preprocessed = (args.rust_raw_strings || doc_comment_key.is_some()).then(|| {
+        let preprocess = |source: &str| {
+            let source = match doc_comment_key {
+                Some(key) => preprocess::convert_doc_comments(source, key),
+                None => source.to_owned(),
+            };
+            if args.rust_raw_strings {
+                preprocess::convert_raw_strings(&source)
+            } else {
+                source
+            }
+        };
+        let dir = preprocess::preprocess_sources(&input_files, preprocess);
+        cmd.arg(format!("--directory={}", dir.0.display()));
+        dir
+    });
+
     // Execute the command and report possible errors.
-    let output = cmd.output().expect("could not execute xgettext");
+    let output = run_xgettext(&mut cmd);
     if !output.status.success() {
         panic!(
             "execution of xgettext failed with status: {}\n{}",
@@ -110,12 +190,143 @@ pub fn create_pot_file(output_file: &str, args: XgettextArguments) {
         remove_lines(output, |line| !line.starts_with("\"POT-Creation-Date"));
     }
 
+    if let Some(comments) = args.extracted_comments {
+        if output.exists() {
+            add_extracted_comments(output, &comments);
+        }
+    }
+
     // No need to rerun the build script if no source file changed.
     for file in input_files {
         println!("cargo:rerun-if-changed={}", file);
     }
 }
 
+/// Adds all options set in `args` to `cmd`.
+fn add_options(cmd: &mut Command, output_file: &str, args: &XgettextArguments) {
+    cmd.arg(format!("--from-code={}", args.code_encoding))
+        .arg(format!("--language={}", args.language))
+        .arg(format!(
+            "--package-name={}",
+            match args.domain {
+                Some(domain) if args.package_name.is_empty() => domain,
+                _ => args.package_name,
+            }
+        ))
+        .arg(format!("--package-version={}", args.package_version))
+        .arg(format!("--copyright-holder={}", args.copyright_holder))
+        .arg(format!("--output={}", output_file));
+
+    add_arg_if(cmd, "--omit-header", args.omit_header);
+    add_arg_if(cmd, "--sort-output", args.sort_output);
+    add_arg_if(cmd, "--sort-by-file", args.sort_by_file);
+    add_arg_if(cmd, "--no-location", args.no_location);
+    if let (Some(mode), false) = (args.location_mode, args.no_location) {
+        cmd.arg(format!("--add-location={}", mode.as_arg()));
+    }
+    add_arg_if(cmd, "--no-wrap", args.no_wrap);
+    add_arg_if(cmd, "--force-po", args.force_pot);
+
+    if let Some(comment) = &args.comment_key {
+        cmd.arg(format!("--add-comment={}", comment));
+    }
+}
+
+/// Returns the paths of all `.rs` files in `dir` and its subdirectories.
+fn rust_files(dir: impl AsRef<Path>) -> Vec<String> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry.path().to_string_lossy().to_string()),
+            Err(_) => None,
+        })
+        .filter(|path| path.ends_with(".rs"))
+        .collect()
+}
+
+/// Returns the path of the POT file of `domain` in the directory of `output_file`.
+fn domain_pot_file(output_file: &str, domain: &str) -> String {
+    Path::new(output_file)
+        .with_file_name(format!("{}.pot", domain))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Adds `comments` as `#.` comments to the entries with matching msgids in the POT file at `path`.
+fn add_extracted_comments(path: &Path, comments: &HashMap<String, String>) {
+    let pot = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("could not read \"{}\": {}", path.to_string_lossy(), err));
+
+    fs::write(path, insert_extracted_comments(&pot, comments)).unwrap_or_else(|err| {
+        panic!(
+            "could not add comments to \"{}\": {}",
+            path.to_string_lossy(),
+            err
+        )
+    });
+}
+
+/// Inserts `comments` above the entries of `pot` whose msgid matches.
+fn insert_extracted_comments(pot: &str, comments: &HashMap<String, String>) -> String {
+    let mut output = Vec::new();
+    let mut entry = Vec::new();
+
+    // Entries are separated by blank lines, so the trailing empty line flushes the last one.
+    for line in pot.lines().chain([""]) {
+        if !line.trim().is_empty() {
+            entry.push(line);
+            continue;
+        }
+
+        // PO files order comments as translator (`#`), extracted (`#.`), reference (`#:`) and
+        // flag or previous msgid (`#,`, `#|`) comments, so the comments go after existing
+        // extracted ones and before the first reference, flag or `msgctxt`/`msgid`.
+        let position = entry.iter().position(|line| {
+            ["#:", "#,", "#|", "msgctxt", "msgid"]
+                .iter()
+                .any(|prefix| line.starts_with(prefix))
+        });
+        let comment = read_msgid(&entry).and_then(|msgid| comments.get(&msgid));
+        if let (Some(position), Some(comment)) = (position, comment) {
+            output.extend(entry.drain(..position).map(str::to_owned));
+            output.extend(comment.lines().map(|line| format!("#. {}", line)));
+        }
+        output.extend(entry.drain(..).map(str::to_owned));
+        output.push(line.to_owned());
+    }
+
+    // Drop the blank line added to flush the last entry.
+    output.pop();
+    output.join("\n") + "\n"
+}
+
+/// Reads the unescaped msgid of the POT entry made up of `lines`.
+///
+/// Returns `None` for the header entry, whose msgid is empty.
+fn read_msgid(lines: &[&str]) -> Option<String> {
+    let start = lines.iter().position(|line| line.starts_with("msgid "))?;
+    let msgid: String = lines[start..]
+        .iter()
+        .enumerate()
+        .take_while(|(i, line)| *i == 0 || line.starts_with('"'))
+        .map(|(i, line)| {
+            let line = if i == 0 {
+                &line["msgid ".len()..]
+            } else {
+                line
+            };
+            let line = line.trim();
+            let line = line
+                .strip_prefix('"')
+                .and_then(|line| line.strip_suffix('"'))
+                .unwrap_or(line);
+            unescape_po_string(line)
+        })
+        .collect();
+
+    (!msgid.is_empty()).then_some(msgid)
+}
+
 /// Remove all lines from `path` for which `keep` returns false.
 fn remove_lines(path: impl AsRef<Path>, keep: impl FnMut(&String) -> bool) {
     let path = path.as_ref();
@@ -140,6 +351,27 @@ fn remove_lines(path: impl AsRef<Path>, keep: impl FnMut(&String) -> bool) {
     });
 }
 
+/// Returns the `xgettext` binary to run, see [create_pot_file].
+fn xgettext_program() -> ffi::OsString {
+    env::var_os("XGETTEXT").unwrap_or_else(|| "xgettext".into())
+}
+
+/// Runs the `xgettext` command `cmd` and returns its output.
+///
+/// Panics with instructions to install gettext if the binary does not exist.
+fn run_xgettext(cmd: &mut Command) -> std::process::Output {
+    match cmd.output() {
+        Ok(output) => output,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => panic!(
+            "could not find `{}`: install gettext (e.g. `apt install gettext` or \
+             `brew install gettext`) or set the XGETTEXT environment variable to the path of \
+             `xgettext`",
+            cmd.get_program().to_string_lossy()
+        ),
+        Err(err) => panic!("could not execute xgettext: {}", err),
+    }
+}
+
 /// Add `flag_str` to `command` as an arguement if `flag` is `true`.
 fn add_arg_if(command: &mut Command, flag_str: &str, flag: bool) {
     if flag {
@@ -155,19 +387,65 @@ fn add_arg_if(command: &mut Command, flag_str: &str, flag: bool) {
 /// See [here](https://www.gnu.org/software/gettext/manual/gettext.html#Overview-of-GNU-gettext) for
 /// more information on the `gettext` workflow.
 pub fn update_mo_files() {
+    update_mo_files_with_report();
+}
+
+/// Like [update_mo_files] but returns a report of the generated MO files.
+pub fn update_mo_files_with_report() -> MoFilesReport {
     const LOCALES_DIR: &str = "locales";
 
     let out_dir = PathBuf::from(env::var("OUT_DIR").expect("failed to read OUT_DIR envvar"))
         .join(LOCALES_DIR);
 
+    compile_mo_files(Path::new(LOCALES_DIR), &out_dir)
+}
+
+/// Generates the MO files of the gettext `domain` like [update_mo_files_with_report].
+///
+/// The PO files of a domain are organized as `locales/<domain>/<locale>.po` and compiled to
+/// `$OUT_DIR/locales/<domain>/<locale>.mo`, so several crates or components can keep separate
+/// catalogs. See [XgettextArguments::domain] for creating the POT file of a domain.
+pub fn update_domain_mo_files(domain: &str) -> MoFilesReport {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("failed to read OUT_DIR envvar"));
+
+    compile_domain_mo_files(Path::new("."), &out_dir, domain)
+}
+
+/// Compiles the PO files of `domain` in `<root>/locales/<domain>` to MO files in
+/// `<out_dir>/locales/<domain>`.
+fn compile_domain_mo_files(root: &Path, out_dir: &Path, domain: &str) -> MoFilesReport {
+    const LOCALES_DIR: &str = "locales";
+
+    compile_mo_files(
+        &root.join(LOCALES_DIR).join(domain),
+        &out_dir.join(LOCALES_DIR).join(domain),
+    )
+}
+
+/// Summary of the MO files generated by [update_mo_files_with_report].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MoFilesReport {
+    /// Number of PO files found in the locales directory.
+    pub po_files: usize,
+    /// Number of MO files written.
+    pub mo_files: usize,
+    /// Number of translated messages per MO file, keyed by file stem (e.g. `de_DE`).
+    pub messages: HashMap<String, usize>,
+}
+
+/// Compiles all PO files in `locales_dir` to MO files in `out_dir` using `msgfmt`.
+fn compile_mo_files(locales_dir: &Path, out_dir: &Path) -> MoFilesReport {
+    let mut report = MoFilesReport::default();
+
     // Make sure the output directory exists.
-    fs::create_dir_all(&out_dir).expect("failed to create locales output dir");
+    fs::create_dir_all(out_dir).expect("failed to create locales output dir");
 
-    for file in fs::read_dir(LOCALES_DIR).expect("failed to read locales directory") {
+    for file in fs::read_dir(locales_dir).expect("failed to read locales directory") {
         let file = file.expect("failed to read po file");
         if file.path().extension() != Some(ffi::OsStr::new("po")) {
             continue;
         }
+        report.po_files += 1;
 
         // Get file names of po and mo files.
         let po_file_path = file.path();
@@ -193,7 +471,813 @@ pub fn update_mo_files() {
                 outcome
             ),
         };
+        report.mo_files += 1;
+        report.messages.insert(
+            po_file_path
+                .file_stem()
+                .expect("failed to get po file name")
+                .to_string_lossy()
+                .into_owned(),
+            count_mo_messages(&output_file),
+        );
 
         println!("cargo:rerun-if-changed={}", file.path().display());
     }
+
+    report
+}
+
+/// Compiles the PO file at `po_path` to a MO file at `mo_path` without `msgfmt`.
+///
+/// Like `msgfmt`, untranslated and fuzzy entries are left out. Use this where the gettext tools
+/// are not installed, e.g. in CI.
+pub fn compile_po_to_mo(
+    po_path: impl AsRef<Path>,
+    mo_path: impl AsRef<Path>,
+) -> Result<(), CompileError> {
+    let po = fs::read_to_string(po_path)?;
+    let entries = po_file::parse_po(&po).map_err(|err| CompileError::Syntax {
+        line: err.line,
+        message: err.message,
+    })?;
+    fs::write(mo_path, mo::write_mo(&entries))?;
+    Ok(())
+}
+
+/// Error returned by [compile_po_to_mo].
+#[derive(Debug, Error)]
+pub enum CompileError {
+    /// Reading the PO file or writing the MO file failed.
+    #[error("Could not compile PO file: {0}")]
+    Io(#[from] io::Error),
+    /// The PO file is invalid.
+    #[error("Invalid PO file in line {line}: {message}")]
+    Syntax {
+        /// The line of the error, starting at 1.
+        line: usize,
+        /// Describes the error.
+        message: String,
+    },
+}
+
+/// Checks that the `msgid` and `msgid_plural` of all plural entries in the POT or PO file at
+/// `pot_path` use the same `{...}` placeholders.
+///
+/// Otherwise an argument like `count` set for the plural form would be missing from the singular
+/// form or vice versa.
+///
+/// # Panics
+///
+/// Panics if the file cannot be read or is invalid.
+pub fn lint_plural_placeholders(pot_path: impl AsRef<Path>) -> Vec<Lint> {
+    read_po_entries(pot_path.as_ref())
+        .into_iter()
+        .filter_map(|entry| {
+            let singular = placeholders(&entry.msgid);
+            let plural = placeholders(entry.msgid_plural.as_deref()?);
+            (singular != plural).then(|| Lint {
+                line: entry.line,
+                msgid: entry.msgid.clone(),
+                message: format!(
+                    "singular uses placeholders {:?} but plural uses {:?}",
+                    singular, plural
+                ),
+            })
+        })
+        .collect()
+}
+
+/// Maps the msgids of the PO file at `po_path` to their previous msgids, which `msgmerge` adds as
+/// `#| msgid` comments to entries it marked as fuzzy after their msgid changed.
+///
+/// This allows reviewers to see what changed in the source of a fuzzy translation. Entries without
+/// previous msgid are skipped.
+///
+/// # Panics
+///
+/// Panics if the file cannot be read or is invalid.
+pub fn extract_previous_msgids(po_path: impl AsRef<Path>) -> HashMap<String, String> {
+    read_po_entries(po_path.as_ref())
+        .into_iter()
+        .filter_map(|entry| Some((entry.msgid, entry.previous_msgid?)))
+        .collect()
+}
+
+/// Audits the translations in the PO file at `po_path`, e.g. to review them before a release.
+///
+/// If `check_identical` is set, translations equal to their msgid are reported as
+/// [AuditReport::identical]. Disable it for catalogs in the language of the msgids, where this is
+/// expected. Msgids without letters outside of placeholders like `"{count}"` are never reported
+/// as identical.
+///
+/// # Panics
+///
+/// Panics if the file cannot be read or is invalid.
+pub fn audit_po_file(po_path: impl AsRef<Path>, check_identical: bool) -> AuditReport {
+    let mut report = AuditReport::default();
+    for entry in read_po_entries(po_path.as_ref()) {
+        if entry.is_header() {
+            continue;
+        }
+
+        // Plural forms besides the first one are compared to the plural msgid.
+        let identical = entry.msgstr.iter().enumerate().any(|(i, msgstr)| {
+            let source = match &entry.msgid_plural {
+                Some(msgid_plural) if i > 0 => msgid_plural,
+                _ => &entry.msgid,
+            };
+            let has_letters = msgstr
+                .split(['{', '}'])
+                .step_by(2)
+                .any(|text| text.chars().any(char::is_alphabetic));
+            msgstr == source && has_letters
+        });
+        if entry.is_untranslated() {
+            report.untranslated.push(entry.msgid);
+        } else if entry.fuzzy {
+            report.fuzzy.push(entry.msgid);
+        } else if check_identical && identical {
+            report.identical.push(entry.msgid);
+        }
+    }
+    report
+}
+
+/// Summary of the problems found by [audit_po_file], listing the msgids of the affected entries.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AuditReport {
+    /// Entries without translation.
+    pub untranslated: Vec<String>,
+    /// Entries marked as fuzzy, which are not used at runtime.
+    pub fuzzy: Vec<String>,
+    /// Entries whose translation equals the msgid.
+    pub identical: Vec<String>,
+}
+
+/// Finds msgids which belong to several entries of the POT or PO file at `pot_path`, i.e. which
+/// are used with different contexts or both with and without a context.
+///
+/// The reports are ordered by the first appearance of the msgid.
+///
+/// # Panics
+///
+/// Panics if the file cannot be read or is invalid.
+pub fn find_duplicate_msgids(pot_path: impl AsRef<Path>) -> Vec<DuplicateReport> {
+    let mut reports: Vec<DuplicateReport> = Vec::new();
+    for entry in read_po_entries(pot_path.as_ref()) {
+        if entry.is_header() {
+            continue;
+        }
+        match reports
+            .iter_mut()
+            .find(|report| report.msgid == entry.msgid)
+        {
+            Some(report) => {
+                report.contexts.push(entry.msgctxt);
+                report.lines.push(entry.line);
+            }
+            None => reports.push(DuplicateReport {
+                msgid: entry.msgid,
+                contexts: vec![entry.msgctxt],
+                lines: vec![entry.line],
+            }),
+        }
+    }
+    reports.retain(|report| report.lines.len() > 1);
+    reports
+}
+
+/// A msgid found in several entries by [find_duplicate_msgids].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DuplicateReport {
+    /// The duplicated msgid.
+    pub msgid: String,
+    /// The context of each entry, `None` for the entry without context.
+    pub contexts: Vec<Option<String>>,
+    /// The line of the `msgid` of each entry, starting at 1.
+    pub lines: Vec<usize>,
+}
+
+/// A problem found in an entry of a POT or PO file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lint {
+    /// The line of the entry's `msgid`, starting at 1.
+    pub line: usize,
+    /// The msgid of the entry.
+    pub msgid: String,
+    /// Describes the problem.
+    pub message: String,
+}
+
+/// Reads and parses all entries of the POT or PO file at `path`.
+fn read_po_entries(path: &Path) -> Vec<po_file::PoEntry> {
+    let po = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("could not read \"{}\": {}", path.to_string_lossy(), err));
+    po_file::parse_po(&po).unwrap_or_else(|err| {
+        panic!(
+            "invalid PO file \"{}\" in line {}: {}",
+            path.to_string_lossy(),
+            err.line,
+            err.message
+        )
+    })
+}
+
+/// Returns the names of the placeholders like `{count}` in `msgid`.
+///
+/// For plural blocks like `{count, plural, ...}` the argument name is returned.
+fn placeholders(msgid: &str) -> BTreeSet<&str> {
+    let mut names = BTreeSet::new();
+    let mut start = None;
+    for (i, c) in msgid.char_indices() {
+        match c {
+            '{' => start = Some(i + 1),
+            '}' | ',' => {
+                let name = start.take().map(|start| msgid[start..i].trim());
+                if let Some(name) = name.filter(|name| {
+                    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+                }) {
+                    names.insert(name);
+                }
+            }
+            _ => (),
+        }
+    }
+    names
+}
+
+/// Counts the messages in the MO file at `path`, not including the header entry.
+fn count_mo_messages(path: &Path) -> usize {
+    let mo = fs::read(path)
+        .unwrap_or_else(|err| panic!("could not read \"{}\": {}", path.to_string_lossy(), err));
+    let entries = crate::mo::read_mo(&mo)
+        .unwrap_or_else(|| panic!("invalid MO file \"{}\"", path.to_string_lossy()));
+
+    // The header is stored as translation of the empty msgid.
+    entries
+        .iter()
+        .filter(|(msgid, _)| !msgid.is_empty())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_options, audit_po_file, compile_domain_mo_files, compile_mo_files, compile_po_to_mo,
+        count_mo_messages, create_pot_file, domain_pot_file, extract_previous_msgids,
+        find_duplicate_msgids, insert_extracted_comments, lint_plural_placeholders, run_xgettext,
+        CompileError, DuplicateReport, LocationMode, XgettextArguments,
+    };
+    use std::collections::HashMap;
+    use std::ffi::OsStr;
+    use std::path::PathBuf;
+    use std::process::Command;
+    use std::{env, fs, process};
+
+    #[test]
+    fn extracted_comments() {
+        let pot = r#"msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+
+# Translator note.
+#. TRANSLATORS: From the source.
+#: src/lib.rs:1
+#, rust-format
+msgid "Name"
+msgstr ""
+
+#: src/lib.rs:2
+msgctxt "menu"
+msgid ""
+"Open \"file\""
+msgstr ""
+"#;
+        let comments = HashMap::from([
+            ("Name".to_owned(), "The name of a person.".to_owned()),
+            ("Open \"file\"".to_owned(), "Menu entry".to_owned()),
+            ("Missing".to_owned(), "Not in the POT.".to_owned()),
+        ]);
+
+        assert_eq!(
+            insert_extracted_comments(pot, &comments),
+            r#"msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+
+# Translator note.
+#. TRANSLATORS: From the source.
+#. The name of a person.
+#: src/lib.rs:1
+#, rust-format
+msgid "Name"
+msgstr ""
+
+#. Menu entry
+#: src/lib.rs:2
+msgctxt "menu"
+msgid ""
+"Open \"file\""
+msgstr ""
+"#
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid MO file")]
+    fn count_truncated_mo_messages() {
+        let path = env::temp_dir().join(format!("getprose-truncated-{}.mo", process::id()));
+        let mut mo = crate::test_support::mo_file(&[("Yes", "Ja")]);
+        mo.truncate(mo.len() - 4);
+        fs::write(&path, mo).unwrap();
+
+        let result = std::panic::catch_unwind(|| count_mo_messages(&path));
+        fs::remove_file(&path).unwrap();
+        std::panic::resume_unwind(result.unwrap_err());
+    }
+
+    #[test]
+    fn mo_files_report() {
+        let dir = env::temp_dir().join(format!("getprose-mo-report-{}", process::id()));
+        let locales_dir = dir.join("locales");
+        fs::create_dir_all(&locales_dir).unwrap();
+        let header = "msgid \"\"\nmsgstr \"Content-Type: text/plain; charset=UTF-8\\n\"\n\n";
+        fs::write(
+            locales_dir.join("de_DE.po"),
+            format!(
+                "{}msgid \"Yes\"\nmsgstr \"Ja\"\n\nmsgid \"No\"\nmsgstr \"Nein\"\n",
+                header
+            ),
+        )
+        .unwrap();
+        fs::write(
+            locales_dir.join("fr_FR.po"),
+            format!("{}msgid \"Yes\"\nmsgstr \"\"\n", header),
+        )
+        .unwrap();
+        fs::write(locales_dir.join("README"), "not a po file").unwrap();
+
+        let report = compile_mo_files(&locales_dir, &dir.join("out"));
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.po_files, 2);
+        assert_eq!(report.mo_files, 2);
+        assert_eq!(
+            report.messages,
+            HashMap::from([("de_DE".to_owned(), 2), ("fr_FR".to_owned(), 0)])
+        );
+    }
+
+    #[test]
+    fn raw_strings() {
+        // The input file has to be relative to be preprocessed.
+        let dir = PathBuf::from(format!("target/getprose-raw-strings-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("raw.rs");
+        let output = dir.join("raw.pot");
+        fs::write(
+            &input,
+            "fn main() {\n    gettext(r#\"Say \"hi\"\"#);\n    gettext(\"plain\");\n}\n",
+        )
+        .unwrap();
+
+        create_pot_file(
+            output.to_str().unwrap(),
+            XgettextArguments::builder()
+                .rust_raw_strings(true)
+                .omit_header(true)
+                .input_files(vec![input.to_string_lossy().into_owned()])
+                .build(),
+        );
+        let pot = fs::read_to_string(&output).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(pot.contains("msgid \"Say \\\"hi\\\"\"\n"), "{}", pot);
+        assert!(pot.contains(&format!("#: {}:3\nmsgid \"plain\"\n", input.display())));
+    }
+
+    #[test]
+    fn doc_comments() {
+        // The input file has to be relative to be preprocessed.
+        let dir = PathBuf::from(format!("target/getprose-doc-comments-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("doc.rs");
+        let output = dir.join("doc.pot");
+        fs::write(
+            &input,
+            "fn main() {\n    /// TRANSLATORS: A greeting.\n    #[rustfmt::skip]\n    \
+             gettext(\"Hello\");\n}\n",
+        )
+        .unwrap();
+
+        create_pot_file(
+            output.to_str().unwrap(),
+            XgettextArguments::builder()
+                .comment_key("TRANSLATORS:")
+                .rust_doc_comments(true)
+                .omit_header(true)
+                .input_files(vec![input.to_string_lossy().into_owned()])
+                .build(),
+        );
+        let pot = fs::read_to_string(&output).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(
+            pot.contains(&format!(
+                "#. TRANSLATORS: A greeting.\n#: {}:4\nmsgid \"Hello\"\n",
+                input.display()
+            )),
+            "{}",
+            pot
+        );
+    }
+
+    /// Returns the arguments passed to `xgettext` for `args`.
+    fn xgettext_args(args: &XgettextArguments) -> Vec<String> {
+        let mut cmd = Command::new("xgettext");
+        add_options(&mut cmd, "out.pot", args);
+        cmd.get_args()
+            .map(OsStr::to_string_lossy)
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn location_mode() {
+        let location_arg = |args: &XgettextArguments| {
+            xgettext_args(args)
+                .into_iter()
+                .find(|arg| arg.starts_with("--add-location"))
+        };
+
+        for (mode, arg) in [
+            (LocationMode::Full, "--add-location=full"),
+            (LocationMode::File, "--add-location=file"),
+            (LocationMode::Never, "--add-location=never"),
+        ] {
+            let args = XgettextArguments::builder().location_mode(mode).build();
+            assert_eq!(location_arg(&args).as_deref(), Some(arg));
+        }
+        assert_eq!(location_arg(&XgettextArguments::builder().build()), None);
+        let args = XgettextArguments::builder()
+            .no_location(true)
+            .location_mode(LocationMode::File)
+            .build();
+        assert_eq!(location_arg(&args), None);
+    }
+
+    #[test]
+    fn domain() {
+        assert_eq!(
+            domain_pot_file("locales/messages.pot", "app"),
+            "locales/app.pot"
+        );
+        assert_eq!(domain_pot_file("messages.pot", "app"), "app.pot");
+
+        let args = xgettext_args(&XgettextArguments::builder().domain("app").build());
+        assert!(args.contains(&"--package-name=app".to_owned()));
+        let args = xgettext_args(
+            &XgettextArguments::builder()
+                .domain("app")
+                .package_name("Application")
+                .build(),
+        );
+        assert!(args.contains(&"--package-name=Application".to_owned()));
+    }
+
+    #[test]
+    fn domain_mo_files() {
+        let dir = PathBuf::from(format!("target/getprose-domain-{}", process::id()));
+        let domain_dir = dir.join("locales/app");
+        fs::create_dir_all(&domain_dir).unwrap();
+        fs::write(
+            domain_dir.join("de_DE.po"),
+            "msgid \"\"\nmsgstr \"Content-Type: text/plain; charset=UTF-8\\n\"\n\n\
+             msgid \"Yes\"\nmsgstr \"Ja\"\n",
+        )
+        .unwrap();
+        // Catalogs of other domains are not compiled.
+        fs::create_dir_all(dir.join("locales/other")).unwrap();
+        fs::write(dir.join("locales/other/fr_FR.po"), "").unwrap();
+
+        let out_dir = dir.join("out");
+        let report = compile_domain_mo_files(&dir, &out_dir, "app");
+        let mo_path = out_dir.join("locales/app/de_DE.mo");
+        let catalog = gettext::Catalog::parse(fs::File::open(&mo_path).unwrap()).unwrap();
+        let other_mo_exists = out_dir.join("locales/other/fr_FR.mo").exists();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(catalog.gettext("Yes"), "Ja");
+        assert!(!other_mo_exists);
+        assert_eq!(report.po_files, 1);
+        assert_eq!(report.mo_files, 1);
+        assert_eq!(report.messages, HashMap::from([("de_DE".to_owned(), 1)]));
+    }
+
+    #[test]
+    fn input_dirs() {
+        // The input files have to be relative to be found by `xgettext`.
+        let dir = PathBuf::from(format!("target/getprose-input-dirs-{}", process::id()));
+        for (subdir, msgid) in [("src/nested", "from src"), ("examples", "from examples")] {
+            fs::create_dir_all(dir.join(subdir)).unwrap();
+            fs::write(
+                dir.join(subdir).join("main.rs"),
+                format!("fn main() {{ gettext(\"{}\"); }}\n", msgid),
+            )
+            .unwrap();
+        }
+        fs::write(dir.join("examples/README.md"), "gettext(\"ignored\")").unwrap();
+        let output = dir.join("messages.pot");
+
+        create_pot_file(
+            output.to_str().unwrap(),
+            XgettextArguments::builder()
+                .omit_header(true)
+                .input_dirs(vec![
+                    dir.join("src").to_string_lossy().into_owned(),
+                    dir.join("examples").to_string_lossy().into_owned(),
+                ])
+                .build(),
+        );
+        let pot = fs::read_to_string(&output).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(pot.contains("msgid \"from src\""), "{}", pot);
+        assert!(pot.contains("msgid \"from examples\""), "{}", pot);
+        assert!(!pot.contains("ignored"), "{}", pot);
+    }
+
+    #[test]
+    fn sort_by_file() {
+        let args = xgettext_args(&XgettextArguments::builder().sort_by_file(true).build());
+        assert!(args.contains(&"--sort-by-file".to_owned()));
+        assert!(!args.contains(&"--sort-output".to_owned()));
+
+        let args = xgettext_args(&XgettextArguments::builder().sort_output(true).build());
+        assert!(!args.contains(&"--sort-by-file".to_owned()));
+        assert!(args.contains(&"--sort-output".to_owned()));
+    }
+
+    #[test]
+    #[should_panic(expected = "mutually exclusive")]
+    fn sort_by_file_and_output() {
+        create_pot_file(
+            "never-written.pot",
+            XgettextArguments::builder()
+                .sort_output(true)
+                .sort_by_file(true)
+                .build(),
+        );
+    }
+
+    #[test]
+    fn po_to_mo() {
+        let dir = env::temp_dir().join(format!("getprose-compile-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let po_path = dir.join("de_DE.po");
+        let mo_path = dir.join("de_DE.mo");
+        fs::write(
+            &po_path,
+            r#"msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+"Plural-Forms: nplurals=2; plural=(n != 1);\n"
+
+#: src/lib.rs:1
+msgid "Name"
+msgstr "Name"
+
+msgctxt "file"
+msgid "Name"
+msgstr ""
+"Datei"
+"name"
+
+msgid "child"
+msgid_plural "children"
+msgstr[0] "Kind"
+msgstr[1] "Kinder"
+
+#, fuzzy
+msgid "Fuzzy"
+msgstr "Unscharf"
+
+msgid "Untranslated"
+msgstr ""
+
+#~ msgid "Obsolete"
+#~ msgstr "Veraltet"
+"#,
+        )
+        .unwrap();
+
+        compile_po_to_mo(&po_path, &mo_path).unwrap();
+        let catalog = gettext::Catalog::parse(fs::File::open(&mo_path).unwrap()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(catalog.gettext("Name"), "Name");
+        assert_eq!(catalog.pgettext("file", "Name"), "Dateiname");
+        assert_eq!(catalog.ngettext("child", "children", 1), "Kind");
+        assert_eq!(catalog.ngettext("child", "children", 2), "Kinder");
+        assert_eq!(catalog.gettext("Fuzzy"), "Fuzzy");
+        assert_eq!(catalog.gettext("Untranslated"), "Untranslated");
+        assert_eq!(catalog.gettext("Obsolete"), "Obsolete");
+
+        let err = compile_po_to_mo(&po_path, &mo_path).unwrap_err();
+        assert!(matches!(err, CompileError::Io(_)));
+    }
+
+    #[test]
+    #[should_panic(expected = "could not find `getprose-missing-xgettext`: install gettext")]
+    fn missing_xgettext() {
+        run_xgettext(&mut Command::new("getprose-missing-xgettext"));
+    }
+
+    #[test]
+    fn plural_placeholders() {
+        let dir = env::temp_dir().join(format!("getprose-lint-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let pot_path = dir.join("messages.pot");
+        fs::write(
+            &pot_path,
+            r#"msgid "{n} file"
+msgid_plural "{count} files"
+msgstr[0] ""
+msgstr[1] ""
+
+msgid "{count} item in {dir}"
+msgid_plural "{count} items in {dir}"
+msgstr[0] ""
+msgstr[1] ""
+
+msgid "{count, plural, one {# day} other {# days}}"
+msgid_plural "{count} days"
+msgstr[0] ""
+msgstr[1] ""
+
+msgid "{name}"
+msgstr ""
+"#,
+        )
+        .unwrap();
+
+        let lints = lint_plural_placeholders(&pot_path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(lints.len(), 1, "{:?}", lints);
+        assert_eq!(lints[0].line, 1);
+        assert_eq!(lints[0].msgid, "{n} file");
+        assert!(lints[0].message.contains("\"n\""), "{}", lints[0].message);
+    }
+
+    #[test]
+    fn audit() {
+        let dir = env::temp_dir().join(format!("getprose-audit-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let po_path = dir.join("de_DE.po");
+        fs::write(
+            &po_path,
+            r#"msgid ""
+msgstr "Content-Type: text/plain; charset=UTF-8\n"
+
+msgid "Name"
+msgstr "Name"
+
+msgid "{count}"
+msgstr "{count}"
+
+msgid "file"
+msgid_plural "files"
+msgstr[0] "Datei"
+msgstr[1] "files"
+
+msgid "Open"
+msgstr "Öffnen"
+
+#, fuzzy
+msgid "Close"
+msgstr "Schließen"
+
+msgid "Save"
+msgstr ""
+"#,
+        )
+        .unwrap();
+
+        let report = audit_po_file(&po_path, true);
+        let without_identical = audit_po_file(&po_path, false);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.identical, ["Name", "file"]);
+        assert_eq!(report.fuzzy, ["Close"]);
+        assert_eq!(report.untranslated, ["Save"]);
+        assert!(without_identical.identical.is_empty());
+        assert_eq!(without_identical.untranslated, ["Save"]);
+    }
+
+    #[test]
+    fn duplicate_msgids() {
+        let dir = env::temp_dir().join(format!("getprose-duplicates-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let pot_path = dir.join("messages.pot");
+        fs::write(
+            &pot_path,
+            r#"msgid ""
+msgstr ""
+
+msgid "Open"
+msgstr ""
+
+msgctxt "menu"
+msgid "Open"
+msgstr ""
+
+msgctxt "menu"
+msgid "Close"
+msgstr ""
+"#,
+        )
+        .unwrap();
+
+        let reports = find_duplicate_msgids(&pot_path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            reports,
+            vec![DuplicateReport {
+                msgid: "Open".to_owned(),
+                contexts: vec![None, Some("menu".to_owned())],
+                lines: vec![4, 8],
+            }]
+        );
+    }
+
+    #[test]
+    fn previous_msgids() {
+        let dir = env::temp_dir().join(format!("getprose-previous-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let po_path = dir.join("de_DE.po");
+        fs::write(
+            &po_path,
+            r#"msgid ""
+msgstr ""
+
+#, fuzzy
+#| msgid "Open file"
+msgid "Open files"
+msgstr "Datei öffnen"
+
+#, fuzzy
+#| msgctxt "menu"
+#| msgid ""
+#| "Save "
+#| "\"all\""
+msgctxt "menu"
+msgid "Save everything"
+msgstr "Alles speichern"
+
+msgid "Close"
+msgstr "Schließen"
+"#,
+        )
+        .unwrap();
+
+        let previous = extract_previous_msgids(&po_path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            previous,
+            HashMap::from([
+                ("Open files".to_owned(), "Open file".to_owned()),
+                ("Save everything".to_owned(), "Save \"all\"".to_owned()),
+            ])
+        );
+    }
+
+    #[test]
+    fn compile_po_with_bom() {
+        let dir = env::temp_dir().join(format!("getprose-bom-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let po_path = dir.join("de_DE.po");
+        let mo_path = dir.join("de_DE.mo");
+        fs::write(
+            &po_path,
+            "\u{feff}msgid \"\"\nmsgstr \"Content-Type: text/plain; charset=UTF-8\\n\"\n\n\
+             msgid \"Yes\"\nmsgstr \"Ja\"\n",
+        )
+        .unwrap();
+
+        compile_po_to_mo(&po_path, &mo_path).unwrap();
+        let catalog = gettext::Catalog::parse(fs::File::open(&mo_path).unwrap()).unwrap();
+        let localizer = crate::Localizer::from_mo_dir(&dir, crate::Locale::de_DE);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(catalog.gettext("Yes"), "Ja");
+        // The compiled file passes the charset check of the loader.
+        let localizer = localizer.unwrap();
+        assert_eq!(
+            localizer.get_catalog(crate::Locale::de_DE).gettext("Yes"),
+            "Ja"
+        );
+    }
 }
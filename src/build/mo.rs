@@ -0,0 +1,31 @@
+//! Writing of binary MO files.
+
+use super::po_file::PoEntry;
+
+/// Builds a MO file containing all translated `entries`.
+///
+/// Like `msgfmt`, untranslated and fuzzy entries are skipped, except for the header. No hash
+/// table is written, so lookups use a binary search over the sorted msgids.
+pub(super) fn write_mo(entries: &[PoEntry]) -> Vec<u8> {
+    let messages: Vec<(String, String)> = entries
+        .iter()
+        .filter(|entry| entry.is_header() || !(entry.fuzzy || entry.is_untranslated()))
+        .map(|entry| {
+            let mut msgid = match &entry.msgctxt {
+                Some(msgctxt) => format!("{}\u{4}{}", msgctxt, entry.msgid),
+                None => entry.msgid.clone(),
+            };
+            if let Some(msgid_plural) = &entry.msgid_plural {
+                msgid.push('\0');
+                msgid.push_str(msgid_plural);
+            }
+            (msgid, entry.msgstr.join("\0"))
+        })
+        .collect();
+
+    let messages: Vec<(&[u8], &[u8])> = messages
+        .iter()
+        .map(|(msgid, msgstr)| (msgid.as_bytes(), msgstr.as_bytes()))
+        .collect();
+    crate::mo::write_mo(&messages)
+}
@@ -0,0 +1,171 @@
+//! Parsing of PO and POT files.
+
+use crate::unescape_po_string;
+
+/// An entry of a PO file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct PoEntry {
+    /// The line of the `msgid` keyword, starting at 1.
+    pub line: usize,
+    /// Whether the entry is marked with the `fuzzy` flag.
+    pub fuzzy: bool,
+    /// The previous msgid from a `#| msgid` comment, as added by `msgmerge` to fuzzy entries.
+    pub previous_msgid: Option<String>,
+    pub msgctxt: Option<String>,
+    pub msgid: String,
+    pub msgid_plural: Option<String>,
+    /// The translation, or the translations of all plural forms if `msgid_plural` is set.
+    pub msgstr: Vec<String>,
+}
+
+impl PoEntry {
+    /// Returns whether the entry is the header, which is stored as translation of the empty msgid.
+    pub fn is_header(&self) -> bool {
+        self.msgctxt.is_none() && self.msgid.is_empty()
+    }
+
+    /// Returns whether all translations are missing.
+    pub fn is_untranslated(&self) -> bool {
+        self.msgstr.iter().all(String::is_empty)
+    }
+}
+
+/// A syntax error in a PO file.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct SyntaxError {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+/// The field of an entry a string belongs to.
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    Msgctxt,
+    Msgid,
+    MsgidPlural,
+    Msgstr(usize),
+}
+
+/// Parses all entries of the PO file `po`.
+///
+/// Obsolete entries (`#~`) and all comments but the `fuzzy` flag and the previous msgid are
+/// skipped. A leading byte order mark is ignored.
+pub(super) fn parse_po(po: &str) -> Result<Vec<PoEntry>, SyntaxError> {
+    // Editors on Windows may save a byte order mark.
+    let po = po.strip_prefix('\u{feff}').unwrap_or(po);
+    let mut entries = Vec::new();
+    let mut entry = PoEntry::default();
+    let mut field = None;
+    // Whether the last `#|` comment belongs to the previous msgid.
+    let mut in_previous_msgid = false;
+
+    for (i, line) in po.lines().enumerate() {
+        let line_number = i + 1;
+        let error = |message: &str| SyntaxError {
+            line: line_number,
+            message: message.to_owned(),
+        };
+        let line = line.trim();
+
+        // Comments and blank lines after a translation end the entry.
+        if line.is_empty() || line.starts_with('#') {
+            if matches!(field, Some(Field::Msgstr(_))) {
+                entries.push(std::mem::take(&mut entry));
+                field = None;
+            }
+            if let Some(flags) = line.strip_prefix("#,") {
+                entry.fuzzy |= flags.split(',').any(|flag| flag.trim() == "fuzzy");
+            }
+            match line.strip_prefix("#|").map(str::trim_start) {
+                Some(previous) => {
+                    let string = match previous.strip_prefix("msgid ") {
+                        Some(string) => {
+                            in_previous_msgid = true;
+                            entry.previous_msgid = Some(String::new());
+                            string
+                        }
+                        None if previous.starts_with('"') => previous,
+                        None => {
+                            in_previous_msgid = false;
+                            continue;
+                        }
+                    };
+                    // Malformed comments are ignored like all other comments.
+                    let string = string
+                        .trim()
+                        .strip_prefix('"')
+                        .and_then(|string| string.strip_suffix('"'));
+                    if let (true, Some(previous_msgid), Some(string)) =
+                        (in_previous_msgid, &mut entry.previous_msgid, string)
+                    {
+                        previous_msgid.push_str(&unescape_po_string(string));
+                    }
+                }
+                None => in_previous_msgid = false,
+            }
+            continue;
+        }
+
+        let (keyword, rest) = match line.find(|c: char| c.is_whitespace()) {
+            Some(end) if !line.starts_with('"') => (&line[..end], line[end..].trim_start()),
+            _ => ("", line),
+        };
+        let next = match keyword {
+            "" => field.ok_or_else(|| error("string without keyword"))?,
+            "msgctxt" => Field::Msgctxt,
+            "msgid" => Field::Msgid,
+            "msgid_plural" => Field::MsgidPlural,
+            "msgstr" => Field::Msgstr(0),
+            keyword => {
+                let index = keyword
+                    .strip_prefix("msgstr[")
+                    .and_then(|index| index.strip_suffix(']'))
+                    .and_then(|index| index.parse().ok())
+                    .ok_or_else(|| error(&format!("unknown keyword `{}`", keyword)))?;
+                Field::Msgstr(index)
+            }
+        };
+
+        // A `msgctxt` or `msgid` right after a translation starts the next entry.
+        if matches!(next, Field::Msgctxt | Field::Msgid) && matches!(field, Some(Field::Msgstr(_)))
+        {
+            entries.push(std::mem::take(&mut entry));
+        }
+
+        let string = rest
+            .strip_prefix('"')
+            .and_then(|rest| rest.strip_suffix('"'))
+            .ok_or_else(|| error("expected a quoted string"))?;
+        let string = unescape_po_string(string);
+
+        if keyword.is_empty() {
+            // Continuation of the previous string.
+        } else if next == Field::Msgid {
+            entry.line = line_number;
+        } else if let Field::Msgstr(index) = next {
+            if index != entry.msgstr.len() {
+                return Err(error("plural forms are not in order"));
+            }
+            entry.msgstr.push(String::new());
+        }
+        match next {
+            Field::Msgctxt => entry
+                .msgctxt
+                .get_or_insert_with(String::new)
+                .push_str(&string),
+            Field::Msgid => entry.msgid.push_str(&string),
+            Field::MsgidPlural => entry
+                .msgid_plural
+                .get_or_insert_with(String::new)
+                .push_str(&string),
+            Field::Msgstr(index) => entry.msgstr[index].push_str(&string),
+        }
+        field = Some(next);
+    }
+
+    if field.is_some() {
+        entries.push(entry);
+    }
+    Ok(entries)
+}
@@ -0,0 +1,286 @@
+//! Preprocessing of Rust sources before they are passed to `xgettext`.
+//!
+//! `xgettext` has no Rust parser, so sources are parsed as C. Constructs C does not know are
+//! rewritten into equivalent C constructs while keeping all line numbers intact.
+
+use std::path::{Component, Path};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{env, fs, process};
+
+/// Number of preprocessing dirs created by this process, to keep concurrent calls apart.
+static DIR_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes preprocessed copies of all relative `input_files` into a new temporary directory and
+/// returns its path.
+///
+/// Passing the directory to `xgettext` via `--directory` makes it read the copies while still
+/// reporting the original paths as locations. Absolute paths are not looked up in the directory,
+/// so they cannot be preprocessed. Paths containing `..` would be copied outside of the directory
+/// and are not preprocessed either. A cargo warning is printed for each of them.
+///
+/// # Panics
+///
+/// Panics if the directory already exists or a file cannot be read or written.
+pub(super) fn preprocess_sources(
+    input_files: &[String],
+    preprocess: impl Fn(&str) -> String,
+) -> TempDir {
+    let dir = env::temp_dir().join(format!(
+        "getprose-xgettext-{}-{}",
+        process::id(),
+        DIR_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir(&dir).unwrap_or_else(|err| {
+        panic!(
+            "could not create preprocessing dir \"{}\": {}",
+            dir.to_string_lossy(),
+            err
+        )
+    });
+    let dir = TempDir(dir);
+
+    for file in input_files.iter().map(Path::new) {
+        let is_nested = file
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if !is_nested {
+            println!(
+                "cargo:warning=\"{}\" is not preprocessed for xgettext as its path is absolute or \
+                 contains `..`",
+                file.to_string_lossy()
+            );
+            continue;
+        }
+        let copy = dir.0.join(file);
+
+        let source = fs::read_to_string(file)
+            .unwrap_or_else(|err| panic!("could not read \"{}\": {}", file.to_string_lossy(), err));
+        if let Some(parent) = copy.parent() {
+            fs::create_dir_all(parent).expect("failed to create preprocessing dir");
+        }
+        fs::write(&copy, preprocess(&source)).unwrap_or_else(|err| {
+            panic!("could not write \"{}\": {}", copy.to_string_lossy(), err)
+        });
+    }
+
+    dir
+}
+
+/// A temporary directory which is removed on drop.
+pub(super) struct TempDir(pub(super) std::path::PathBuf);
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Converts all raw string literals like `r#"..."#` in `source` to regular string literals.
+///
+/// Newlines inside raw strings are escaped and appended after the literal to keep the line numbers
+/// of the following code.
+pub(super) fn convert_raw_strings(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut converted = String::with_capacity(source.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let start = i;
+        match chars[i] {
+            // Line comment.
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            // Block comment, which may be nested in Rust.
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                let mut depth = 0;
+                while i < chars.len() {
+                    if chars[i] == '/' && chars.get(i + 1) == Some(&'*') {
+                        depth += 1;
+                        i += 2;
+                    } else if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                        depth -= 1;
+                        i += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+            // Regular string literal.
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    i += if chars[i] == '\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            // Char literal or lifetime, a char literal may contain `"`.
+            '\'' => {
+                if chars.get(i + 1) == Some(&'\\') {
+                    // Skip the escaped char, which may be `'` itself.
+                    i += 3;
+                    while i < chars.len() && chars[i] != '\'' {
+                        i += 1;
+                    }
+                    i += 1;
+                } else if chars.get(i + 2) == Some(&'\'') {
+                    i += 3;
+                } else {
+                    i += 1;
+                }
+            }
+            // Raw string literal, unless the `r` ends an identifier.
+            'r' if !(i > 0 && is_ident_char(chars[i - 1])) => {
+                let hashes = chars[i + 1..].iter().take_while(|&&c| c == '#').count();
+                if chars.get(i + 1 + hashes) != Some(&'"') {
+                    converted.push('r');
+                    i += 1;
+                    continue;
+                }
+
+                let content_start = i + 2 + hashes;
+                let mut end = content_start;
+                while end < chars.len()
+                    && !(chars[end] == '"'
+                        && chars[end + 1..].iter().take_while(|&&c| c == '#').count() >= hashes)
+                {
+                    end += 1;
+                }
+
+                let mut newlines = 0;
+                converted.push('"');
+                for &c in &chars[content_start..end.min(chars.len())] {
+                    match c {
+                        '\\' => converted.push_str("\\\\"),
+                        '"' => converted.push_str("\\\""),
+                        '\n' => {
+                            converted.push_str("\\n");
+                            newlines += 1;
+                        }
+                        c => converted.push(c),
+                    }
+                }
+                converted.push('"');
+                converted.extend(std::iter::repeat('\n').take(newlines));
+
+                i = end + 1 + hashes;
+                continue;
+            }
+            // Skip whole identifiers to not mistake a trailing `r` for a raw string.
+            c if is_ident_char(c) => {
+                while i < chars.len() && is_ident_char(chars[i]) {
+                    i += 1;
+                }
+            }
+            _ => i += 1,
+        }
+        converted.extend(&chars[start..i.min(chars.len())]);
+    }
+
+    converted
+}
+
+/// Checks whether `c` can be part of an identifier.
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Converts doc comments (`///` or `//!`) starting with `key` in `source` to line comments and
+/// moves them below directly following attributes.
+///
+/// `xgettext` only attaches comments to a msgid if no preprocessor directive is in between, which
+/// is what attributes like `#[rustfmt::skip]` look like to its C parser. The lines are only
+/// reordered, so the line numbers of the following code are kept.
+pub(super) fn convert_doc_comments(source: &str, key: &str) -> String {
+    let lines: Vec<&str> = source.split_inclusive('\n').collect();
+    let mut converted = String::with_capacity(source.len());
+    let mut i = 0;
+
+    while i < lines.len() {
+        let is_note = split_doc_comment(lines[i])
+            .map_or(false, |(_, text)| text.trim_start().starts_with(key));
+        if !is_note {
+            converted.push_str(lines[i]);
+            i += 1;
+            continue;
+        }
+
+        let comments_end = (i..lines.len())
+            .find(|&j| split_doc_comment(lines[j]).is_none())
+            .unwrap_or(lines.len());
+        let attributes_end = (comments_end..lines.len())
+            .find(|&j| !lines[j].trim_start().starts_with("#["))
+            .unwrap_or(lines.len());
+        converted.extend(lines[comments_end..attributes_end].iter().copied());
+        for (indent, text) in lines[i..comments_end]
+            .iter()
+            .filter_map(|line| split_doc_comment(line))
+        {
+            converted.push_str(indent);
+            converted.push_str("//");
+            converted.push_str(text);
+        }
+        i = attributes_end;
+    }
+
+    converted
+}
+
+/// Splits a doc comment line into its indentation and the text after `///` or `//!`.
+fn split_doc_comment(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start();
+    let text = trimmed
+        .strip_prefix("///")
+        .or_else(|| trimmed.strip_prefix("//!"))?;
+    Some((&line[..line.len() - trimmed.len()], text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{convert_doc_comments, convert_raw_strings, preprocess_sources};
+    use std::fs;
+
+    #[test]
+    fn outside_paths() {
+        let files = [
+            "../getprose-outside.rs".to_owned(),
+            "/getprose-abs.rs".to_owned(),
+        ];
+        let dir = preprocess_sources(&files, str::to_owned);
+
+        assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 0);
+        assert!(!dir.0.join("../getprose-outside.rs").exists());
+    }
+
+    #[test]
+    fn doc_comments() {
+        let source = "/// Docs.\nfn f() {\n    /// TRANSLATORS: A note.\n    /// More.\n    \
+                      #[rustfmt::skip]\n    gettext(\"a\");\n    //! TRANSLATORS: Inner.\n}";
+        assert_eq!(
+            convert_doc_comments(source, "TRANSLATORS:"),
+            "/// Docs.\nfn f() {\n    #[rustfmt::skip]\n    // TRANSLATORS: A note.\n    // More.\n    \
+             gettext(\"a\");\n    // TRANSLATORS: Inner.\n}"
+        );
+    }
+
+    #[test]
+    fn raw_strings() {
+        assert_eq!(
+            convert_raw_strings(r####"gettext(r#"Say "hi""#);"####),
+            r#"gettext("Say \"hi\"");"#
+        );
+        assert_eq!(
+            convert_raw_strings("gettext(r\"C:\\dir\nnext\"); // r\"kept\"\nfoo"),
+            "gettext(\"C:\\\\dir\\nnext\"\n); // r\"kept\"\nfoo"
+        );
+
+        // Identifiers ending in `r`, lifetimes, chars and regular strings are kept.
+        let source = r#"for bar in br"x" { f::<'a>('"', "r\"x\"", 'r', '\''); }"#;
+        assert_eq!(convert_raw_strings(source), source);
+    }
+}
@@ -0,0 +1,121 @@
+//! Locale-aware comparison of strings.
+
+use crate::Locale;
+
+/// A key to sort strings according to the collation rules of a [Locale].
+///
+/// Created with [Locale::sort_key].
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct SortKey {
+    /// Weights of the base letters, ignoring accents and case.
+    primary: Vec<u32>,
+    /// Weights of the accents, unaccented letters sort first.
+    secondary: Vec<u32>,
+    /// Weights of the case, lowercase letters sort first.
+    tertiary: Vec<bool>,
+    /// The original string to make the order total.
+    original: String,
+}
+
+impl Locale {
+    /// Returns a key to sort `s` according to the collation rules of the [Locale].
+    ///
+    /// This is a simplified collation: strings are compared by their base letters first, then by
+    /// their accents and finally by case. Letters with diacritics sort with their base letters,
+    /// e.g. German `ä` sorts like `a` and `ß` like `ss` (DIN 5007-1). Spanish `ñ` sorts as a
+    /// separate letter after `n`.
+    pub fn sort_key(&self, s: &str) -> SortKey {
+        let mut key = SortKey {
+            primary: Vec::with_capacity(s.len()),
+            secondary: Vec::with_capacity(s.len()),
+            tertiary: Vec::with_capacity(s.len()),
+            original: s.to_owned(),
+        };
+
+        for c in s.chars() {
+            let uppercase = c.is_uppercase();
+            for lower in c.to_lowercase() {
+                let mut push = |primary: u32, secondary: u32| {
+                    key.primary.push(primary);
+                    key.secondary.push(secondary);
+                    key.tertiary.push(uppercase);
+                };
+
+                match (self, base_letters(lower)) {
+                    (Locale::es_ES, _) if lower == 'ñ' => push(u32::from('n') << 1 | 1, 0),
+                    (_, Some(base)) => {
+                        for base in base.chars() {
+                            push(u32::from(base) << 1, lower.into());
+                        }
+                    }
+                    (_, None) => push(u32::from(lower) << 1, 0),
+                }
+            }
+        }
+
+        key
+    }
+}
+
+/// Sorts `strings` according to the collation rules of `locale`.
+///
+/// See [Locale::sort_key] for the rules applied.
+pub fn sort_strings(strings: &mut [&str], locale: Locale) {
+    strings.sort_by_cached_key(|s| locale.sort_key(s));
+}
+
+/// Returns the base letters of the lowercase `c` if it has diacritics.
+fn base_letters(c: char) -> Option<&'static str> {
+    let base = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'č' => "c",
+        'ď' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' => "i",
+        'ł' => "l",
+        'ñ' | 'ń' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+        'œ' => "oe",
+        'ř' => "r",
+        'ß' => "ss",
+        'ś' | 'š' => "s",
+        'ť' => "t",
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => "u",
+        'ý' | 'ÿ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        'ё' => "е",
+        _ => return None,
+    };
+    Some(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sort_strings;
+    use crate::Locale;
+
+    #[test]
+    fn collation() {
+        let mut strings = ["z", "ä", "a"];
+        sort_strings(&mut strings, Locale::de_DE);
+        assert_eq!(strings, ["a", "ä", "z"]);
+
+        let mut strings = [
+            "Zoo", "Straße", "Strasse", "Äpfel", "apfel", "Apfel", "Stroh",
+        ];
+        sort_strings(&mut strings, Locale::de_DE);
+        assert_eq!(
+            strings,
+            ["apfel", "Apfel", "Äpfel", "Strasse", "Straße", "Stroh", "Zoo"]
+        );
+
+        let mut strings = ["élan", "etre", "être", "ecole", "zèbre"];
+        sort_strings(&mut strings, Locale::fr_FR);
+        assert_eq!(strings, ["ecole", "élan", "etre", "être", "zèbre"]);
+
+        let mut strings = ["oso", "ñu", "nube"];
+        sort_strings(&mut strings, Locale::es_ES);
+        assert_eq!(strings, ["nube", "ñu", "oso"]);
+    }
+}
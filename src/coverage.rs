@@ -0,0 +1,123 @@
+//! Checks how much of a set of msgids is translated.
+
+use crate::{CatalogLike, Locale, Localizer};
+use thiserror::Error;
+
+impl<C: CatalogLike> Localizer<C> {
+    /// Returns the fraction of `msgids` translated in the catalog for `locale`.
+    ///
+    /// Locales without a catalog have a coverage of `0.0`, an empty list of msgids is fully
+    /// covered. See [Localizer::untranslated] for which msgids count as untranslated.
+    pub fn coverage(&self, locale: impl Into<Locale>, msgids: &[&str]) -> f32 {
+        if msgids.is_empty() {
+            return 1.0;
+        }
+        let untranslated = self.untranslated(locale, msgids).len();
+        (msgids.len() - untranslated) as f32 / msgids.len() as f32
+    }
+
+    /// Returns all `msgids` not translated in the catalog for `locale`.
+    ///
+    /// Since catalogs return the msgid itself for missing translations, translations identical to
+    /// their msgid count as untranslated as well. All msgids are untranslated for locales without a
+    /// catalog.
+    pub fn untranslated<'m>(&self, locale: impl Into<Locale>, msgids: &[&'m str]) -> Vec<&'m str> {
+        match self.catalogs.get(&locale.into()) {
+            Some(catalog) => msgids
+                .iter()
+                .copied()
+                .filter(|&msgid| catalog.gettext(msgid) == msgid)
+                .collect(),
+            None => msgids.to_vec(),
+        }
+    }
+
+    /// Checks that each loaded locale except the fallback covers at least the fraction `min` of
+    /// `msgids`.
+    ///
+    /// Meant to be called from a test to fail CI if translations are missing. The fallback locale
+    /// is skipped, since it is usually the source language whose catalog is empty. The msgids have
+    /// to be passed, as catalogs cannot list the msgids they contain. They can be read from the
+    /// POT file of the crate.
+    pub fn coverage_report(&self, msgids: &[&str], min: f32) -> Result<(), CoverageError> {
+        let mut locales: Vec<_> = self
+            .iter_locales()
+            .filter(|&&locale| locale != self.fallback())
+            .map(|&locale| (locale, self.coverage(locale, msgids)))
+            .filter(|&(_, coverage)| coverage < min)
+            .collect();
+        if locales.is_empty() {
+            return Ok(());
+        }
+
+        locales.sort_by_key(|(locale, _)| locale.as_str());
+        Err(CoverageError { min, locales })
+    }
+}
+
+/// The translation coverage of some locales is below the required minimum.
+#[derive(Clone, Debug, Error, PartialEq)]
+#[error(
+    "Translation coverage is below {:.1}% for {}",
+    .min * 100.0,
+    format_locales(.locales)
+)]
+pub struct CoverageError {
+    /// The required minimum coverage.
+    pub min: f32,
+    /// The locales below `min` with their coverage, sorted by locale.
+    pub locales: Vec<(Locale, f32)>,
+}
+
+/// Lists `locales` with their coverage in percent.
+fn format_locales(locales: &[(Locale, f32)]) -> String {
+    locales
+        .iter()
+        .map(|(locale, coverage)| format!("{} ({:.1}%)", locale.as_str(), coverage * 100.0))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CoverageError;
+    use crate::test_support::catalog;
+    use crate::{Locale, Localizer};
+    use std::collections::HashMap;
+
+    #[test]
+    fn coverage_report() {
+        let localizer = Localizer::new(
+            HashMap::from([
+                (Locale::en_GB, catalog(&[])),
+                (Locale::de_DE, catalog(&[("Yes", "Ja"), ("No", "Nein")])),
+                (Locale::fr_FR, catalog(&[("Yes", "Oui")])),
+                (Locale::it_IT, catalog(&[("Yes", "Sì")])),
+            ]),
+            Locale::en_GB,
+        )
+        .unwrap();
+        let msgids = ["Yes", "No"];
+
+        assert_eq!(localizer.coverage(Locale::de_DE, &msgids), 1.0);
+        assert_eq!(localizer.coverage(Locale::fr_FR, &msgids), 0.5);
+        assert_eq!(localizer.coverage(Locale::es_ES, &msgids), 0.0);
+        assert_eq!(localizer.untranslated(Locale::fr_FR, &msgids), ["No"]);
+
+        // The empty catalog of the fallback locale is not checked.
+        assert_eq!(localizer.coverage_report(&[], 1.0), Ok(()));
+        assert_eq!(localizer.coverage_report(&msgids, 0.5), Ok(()));
+        let err = localizer.coverage_report(&msgids, 0.9).unwrap_err();
+        assert_eq!(
+            err,
+            CoverageError {
+                min: 0.9,
+                locales: vec![(Locale::fr_FR, 0.5), (Locale::it_IT, 0.5)],
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Translation coverage is below 90.0% for fr_FR (50.0%), it_IT (50.0%)"
+        );
+    }
+}
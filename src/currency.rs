@@ -0,0 +1,311 @@
+//! Currencies and their properties.
+
+use crate::{format_f64, Locale};
+
+/// The supported currencies.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Currency {
+    /// Swiss franc
+    CHF,
+    /// Euro
+    EUR,
+    /// Pound sterling
+    GBP,
+    /// Japanese yen
+    JPY,
+    /// Russian ruble
+    RUB,
+    /// United States dollar
+    USD,
+}
+
+impl Currency {
+    /// Returns the ISO 4217 code of the currency, e.g. `"EUR"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::CHF => "CHF",
+            Currency::EUR => "EUR",
+            Currency::GBP => "GBP",
+            Currency::JPY => "JPY",
+            Currency::RUB => "RUB",
+            Currency::USD => "USD",
+        }
+    }
+
+    /// Returns the number of digits after the decimal point used for amounts in the currency,
+    /// i.e. its ISO 4217 minor unit.
+    ///
+    /// Can be used as precision for [format_f64](crate::format_f64).
+    pub fn default_precision(&self) -> u8 {
+        match self {
+            Currency::JPY => 0,
+            Currency::CHF | Currency::EUR | Currency::GBP | Currency::RUB | Currency::USD => 2,
+        }
+    }
+
+    /// Returns the symbol of the currency, e.g. `"€"`. The Swiss franc uses its code.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::CHF => "CHF",
+            Currency::EUR => "€",
+            Currency::GBP => "£",
+            Currency::JPY => "¥",
+            Currency::RUB => "₽",
+            Currency::USD => "$",
+        }
+    }
+
+    /// Returns the name of the currency in `locale`, e.g. `"Britisches Pfund"` in German.
+    pub fn name(&self, locale: Locale) -> &'static str {
+        let [chf, eur, gbp, jpy, rub, usd] = match locale {
+            Locale::de_DE => [
+                "Schweizer Franken",
+                "Euro",
+                "Britisches Pfund",
+                "Japanischer Yen",
+                "Russischer Rubel",
+                "US-Dollar",
+            ],
+            Locale::en_GB => [
+                "Swiss franc",
+                "euro",
+                "British pound",
+                "Japanese yen",
+                "Russian ruble",
+                "US dollar",
+            ],
+            Locale::es_ES => [
+                "franco suizo",
+                "euro",
+                "libra esterlina",
+                "yen",
+                "rublo ruso",
+                "dólar estadounidense",
+            ],
+            Locale::fr_FR => [
+                "franc suisse",
+                "euro",
+                "livre sterling",
+                "yen japonais",
+                "rouble russe",
+                "dollar des États-Unis",
+            ],
+            Locale::it_IT => [
+                "franco svizzero",
+                "euro",
+                "sterlina britannica",
+                "yen giapponese",
+                "rublo russo",
+                "dollaro statunitense",
+            ],
+            Locale::pt_PT => [
+                "franco suíço",
+                "euro",
+                "libra esterlina britânica",
+                "iene japonês",
+                "rublo russo",
+                "dólar dos Estados Unidos",
+            ],
+            Locale::ru_RU => [
+                "швейцарский франк",
+                "евро",
+                "британский фунт стерлингов",
+                "японская иена",
+                "российский рубль",
+                "доллар США",
+            ],
+        };
+        match self {
+            Currency::CHF => chf,
+            Currency::EUR => eur,
+            Currency::GBP => gbp,
+            Currency::JPY => jpy,
+            Currency::RUB => rub,
+            Currency::USD => usd,
+        }
+    }
+}
+
+impl Locale {
+    /// Returns the currency of the region of the [Locale], e.g. [Currency::EUR] for
+    /// [Locale::de_DE].
+    pub fn default_currency(&self) -> Currency {
+        match self {
+            Locale::en_GB => Currency::GBP,
+            Locale::ru_RU => Currency::RUB,
+            Locale::de_DE | Locale::es_ES | Locale::fr_FR | Locale::it_IT | Locale::pt_PT => {
+                Currency::EUR
+            }
+        }
+    }
+}
+
+/// How the currency is shown by [format_currency].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum CurrencyDisplay {
+    /// The symbol, e.g. `1.234,56 €`, see [Currency::symbol].
+    Symbol,
+    /// The ISO 4217 code, e.g. `1.234,56 EUR`, see [Currency::code].
+    Code,
+    /// The localized name, e.g. `1.234,56 Euro`, see [Currency::name].
+    Name,
+}
+
+/// Formats `amount` in `currency` according to `locale`, showing the currency as set by
+/// `display`.
+///
+/// The amount is rounded to [Currency::default_precision]. Symbols and codes are placed like in
+/// [format_currency_accounting], names always follow the amount and are not inflected. The
+/// currency is separated from the amount by a no-break space unless it is a symbol in front of it.
+/// The minus sign of negative amounts comes first, e.g. `-£3.50`.
+pub fn format_currency<N: Into<f64>>(
+    amount: N,
+    currency: Currency,
+    display: CurrencyDisplay,
+    locale: Locale,
+) -> String {
+    let amount = amount.into();
+    let formatted = format_f64(amount.abs(), currency.default_precision(), locale);
+    let formatted = match display {
+        CurrencyDisplay::Symbol => with_symbol(&formatted, currency.symbol(), locale),
+        CurrencyDisplay::Code => with_symbol(&formatted, currency.code(), locale),
+        CurrencyDisplay::Name => format!("{}\u{a0}{}", formatted, currency.name(locale)),
+    };
+
+    if amount < 0.0 && !is_zero(&formatted) {
+        format!("{}{}", locale.num_format_locale().minus_sign(), formatted)
+    } else {
+        formatted
+    }
+}
+
+/// Formats `amount` in the [Locale::default_currency] of `locale` with its symbol like
+/// [format_currency], e.g. `1.234,56 €` in German.
+pub fn format_currency_default<N: Into<f64>>(amount: N, locale: Locale) -> String {
+    format_currency(
+        amount,
+        locale.default_currency(),
+        CurrencyDisplay::Symbol,
+        locale,
+    )
+}
+
+/// Formats `amount` in `currency` in accounting style, i.e. negative amounts are put in
+/// parentheses instead of having a minus sign, e.g. `(1.234,56 €)` in German.
+///
+/// The amount is rounded to [Currency::default_precision]. English puts the symbol in front of
+/// the amount, e.g. `(£1,234.56)`, all other locales behind it, separated by a no-break space.
+pub fn format_currency_accounting<N: Into<f64>>(
+    amount: N,
+    currency: Currency,
+    locale: Locale,
+) -> String {
+    let amount = amount.into();
+    let formatted = format_f64(amount.abs(), currency.default_precision(), locale);
+    let formatted = with_symbol(&formatted, currency.symbol(), locale);
+
+    if amount < 0.0 && !is_zero(&formatted) {
+        format!("({})", formatted)
+    } else {
+        formatted
+    }
+}
+
+/// Checks whether the formatted amount is zero, since amounts rounding to zero are not negative.
+fn is_zero(formatted: &str) -> bool {
+    !formatted.chars().any(|c| ('1'..='9').contains(&c))
+}
+
+/// Adds the currency `symbol` to the formatted `amount` where `locale` places it.
+fn with_symbol(amount: &str, symbol: &str, locale: Locale) -> String {
+    match locale {
+        // Alphabetic symbols are separated from the amount.
+        Locale::en_GB if symbol.chars().all(char::is_alphabetic) => {
+            format!("{}\u{a0}{}", symbol, amount)
+        }
+        Locale::en_GB => format!("{}{}", symbol, amount),
+        _ => format!("{}\u{a0}{}", amount, symbol),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        format_currency, format_currency_accounting, format_currency_default, Currency,
+        CurrencyDisplay,
+    };
+    use crate::Locale;
+
+    #[test]
+    fn default_precision() {
+        assert_eq!(Currency::CHF.default_precision(), 2);
+        assert_eq!(Currency::EUR.default_precision(), 2);
+        assert_eq!(Currency::GBP.default_precision(), 2);
+        assert_eq!(Currency::JPY.default_precision(), 0);
+        assert_eq!(Currency::RUB.default_precision(), 2);
+        assert_eq!(Currency::USD.default_precision(), 2);
+    }
+
+    #[test]
+    fn accounting() {
+        let format = format_currency_accounting;
+
+        assert_eq!(
+            format(-1234.56, Currency::EUR, Locale::de_DE),
+            "(1.234,56\u{a0}€)"
+        );
+        assert_eq!(
+            format(1234.56, Currency::EUR, Locale::de_DE),
+            "1.234,56\u{a0}€"
+        );
+        assert_eq!(
+            format(-1234.56, Currency::GBP, Locale::en_GB),
+            "(£1,234.56)"
+        );
+        assert_eq!(
+            format(-5.0, Currency::CHF, Locale::en_GB),
+            "(CHF\u{a0}5.00)"
+        );
+        assert_eq!(format(-1234.4, Currency::JPY, Locale::en_GB), "(¥1,234)");
+        assert_eq!(format(-0.001, Currency::EUR, Locale::de_DE), "0,00\u{a0}€");
+    }
+
+    #[test]
+    fn display() {
+        let format = |display| format_currency(1234.56, Currency::EUR, display, Locale::de_DE);
+
+        assert_eq!(format(CurrencyDisplay::Symbol), "1.234,56\u{a0}€");
+        assert_eq!(format(CurrencyDisplay::Code), "1.234,56\u{a0}EUR");
+        assert_eq!(format(CurrencyDisplay::Name), "1.234,56\u{a0}Euro");
+        assert_eq!(
+            format_currency(-3.5, Currency::GBP, CurrencyDisplay::Name, Locale::de_DE),
+            "-3,50\u{a0}Britisches Pfund"
+        );
+        assert_eq!(
+            format_currency(-3.5, Currency::GBP, CurrencyDisplay::Code, Locale::en_GB),
+            "-GBP\u{a0}3.50"
+        );
+        assert_eq!(
+            format_currency(-3.5, Currency::GBP, CurrencyDisplay::Symbol, Locale::en_GB),
+            "-£3.50"
+        );
+    }
+
+    #[test]
+    fn default_currency() {
+        assert_eq!(Locale::de_DE.default_currency(), Currency::EUR);
+        assert_eq!(Locale::en_GB.default_currency(), Currency::GBP);
+        assert_eq!(Locale::es_ES.default_currency(), Currency::EUR);
+        assert_eq!(Locale::fr_FR.default_currency(), Currency::EUR);
+        assert_eq!(Locale::it_IT.default_currency(), Currency::EUR);
+        assert_eq!(Locale::pt_PT.default_currency(), Currency::EUR);
+        assert_eq!(Locale::ru_RU.default_currency(), Currency::RUB);
+
+        assert_eq!(
+            format_currency_default(1234.56, Locale::de_DE),
+            "1.234,56\u{a0}€"
+        );
+        assert_eq!(format_currency_default(1234.56, Locale::en_GB), "£1,234.56");
+    }
+}
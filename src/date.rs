@@ -0,0 +1,717 @@
+//! Localized formatting of dates.
+
+use crate::Locale;
+#[cfg(feature = "chrono")]
+use crate::{format_int, PluralCategory};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+
+/// Formats `date` relative to `now` in calendar days, e.g. "gestern" or "letzte Woche".
+///
+/// - The same, previous and next day are "today", "yesterday" and "tomorrow".
+/// - Up to six days are counted, e.g. "3 days ago" or "in 3 days".
+/// - Dates in the previous or next calendar week (starting on Monday) are "last week" and
+///   "next week".
+/// - All other dates are formatted numerically, e.g. `14.10.2026`.
+///
+/// Unlike a duration based formatting only the calendar days are considered, so a date is
+/// "yesterday" even if it was only a few minutes before midnight.
+#[cfg(feature = "chrono")]
+pub fn format_relative_date(date: NaiveDate, now: NaiveDate, locale: Locale) -> String {
+    let words = RelativeDateWords::of(locale);
+    let days = date.signed_duration_since(now).num_days();
+    let monday =
+        |date: NaiveDate| date - Duration::days(date.weekday().num_days_from_monday().into());
+    let weeks = monday(date).signed_duration_since(monday(now)).num_weeks();
+    let count = format_int(days.unsigned_abs(), locale);
+    let plural = locale.select_plural(days.unsigned_abs());
+
+    match (days, weeks) {
+        (0, _) => words.today.to_owned(),
+        (-1, _) => words.yesterday.to_owned(),
+        (1, _) => words.tomorrow.to_owned(),
+        (-6..=-2, _) => (words.days_ago)(&count, plural),
+        (2..=6, _) => (words.in_days)(&count, plural),
+        (_, -1) => words.last_week.to_owned(),
+        (_, 1) => words.next_week.to_owned(),
+        _ => {
+            let pattern = match locale {
+                Locale::de_DE | Locale::ru_RU => "%d.%m.%Y",
+                _ => "%d/%m/%Y",
+            };
+            date.format(pattern).to_string()
+        }
+    }
+}
+
+/// The words used by [format_relative_date].
+#[cfg(feature = "chrono")]
+struct RelativeDateWords {
+    today: &'static str,
+    yesterday: &'static str,
+    tomorrow: &'static str,
+    last_week: &'static str,
+    next_week: &'static str,
+    /// Formats the number of days in the past, the count is at least 2.
+    days_ago: fn(&str, PluralCategory) -> String,
+    /// Formats the number of days in the future, the count is at least 2.
+    in_days: fn(&str, PluralCategory) -> String,
+}
+
+#[cfg(feature = "chrono")]
+impl RelativeDateWords {
+    fn of(locale: Locale) -> Self {
+        match locale {
+            Locale::de_DE => RelativeDateWords {
+                today: "heute",
+                yesterday: "gestern",
+                tomorrow: "morgen",
+                last_week: "letzte Woche",
+                next_week: "nächste Woche",
+                days_ago: |n, _| format!("vor {} Tagen", n),
+                in_days: |n, _| format!("in {} Tagen", n),
+            },
+            Locale::en_GB => RelativeDateWords {
+                today: "today",
+                yesterday: "yesterday",
+                tomorrow: "tomorrow",
+                last_week: "last week",
+                next_week: "next week",
+                days_ago: |n, _| format!("{} days ago", n),
+                in_days: |n, _| format!("in {} days", n),
+            },
+            Locale::es_ES => RelativeDateWords {
+                today: "hoy",
+                yesterday: "ayer",
+                tomorrow: "mañana",
+                last_week: "la semana pasada",
+                next_week: "la próxima semana",
+                days_ago: |n, _| format!("hace {} días", n),
+                in_days: |n, _| format!("dentro de {} días", n),
+            },
+            Locale::fr_FR => RelativeDateWords {
+                today: "aujourd’hui",
+                yesterday: "hier",
+                tomorrow: "demain",
+                last_week: "la semaine dernière",
+                next_week: "la semaine prochaine",
+                days_ago: |n, _| format!("il y a {} jours", n),
+                in_days: |n, _| format!("dans {} jours", n),
+            },
+            Locale::it_IT => RelativeDateWords {
+                today: "oggi",
+                yesterday: "ieri",
+                tomorrow: "domani",
+                last_week: "la settimana scorsa",
+                next_week: "la settimana prossima",
+                days_ago: |n, _| format!("{} giorni fa", n),
+                in_days: |n, _| format!("tra {} giorni", n),
+            },
+            Locale::pt_PT => RelativeDateWords {
+                today: "hoje",
+                yesterday: "ontem",
+                tomorrow: "amanhã",
+                last_week: "a semana passada",
+                next_week: "a próxima semana",
+                days_ago: |n, _| format!("há {} dias", n),
+                in_days: |n, _| format!("dentro de {} dias", n),
+            },
+            Locale::ru_RU => RelativeDateWords {
+                today: "сегодня",
+                yesterday: "вчера",
+                tomorrow: "завтра",
+                last_week: "на прошлой неделе",
+                next_week: "на следующей неделе",
+                days_ago: |n, plural| format!("{} {} назад", n, russian_days(plural)),
+                in_days: |n, plural| format!("через {} {}", n, russian_days(plural)),
+            },
+        }
+    }
+}
+
+/// Formats the time from now until `target`, e.g. "in 5 minutes".
+///
+/// A `target` in the past is formatted like [format_time_since], e.g. "5 minutes ago". See
+/// [format_time_until_from] for the units.
+#[cfg(feature = "chrono")]
+pub fn format_time_until<Tz: TimeZone>(target: DateTime<Tz>, locale: Locale) -> String {
+    format_time_until_from(target, Utc::now(), locale)
+}
+
+/// Formats the time from `now` until `target`, e.g. "in 5 minutes".
+///
+/// The largest unit of seconds, minutes, hours or days fitting into the time is used, rounding
+/// down, e.g. 90 minutes are "in 1 hour". Less than a second is "now".
+#[cfg(feature = "chrono")]
+pub fn format_time_until_from<Tz: TimeZone, NowTz: TimeZone>(
+    target: DateTime<Tz>,
+    now: DateTime<NowTz>,
+    locale: Locale,
+) -> String {
+    format_relative_time(target.signed_duration_since(now), locale)
+}
+
+/// Formats the time since `past` until now, e.g. "5 minutes ago".
+///
+/// A `past` in the future is formatted like [format_time_until], e.g. "in 5 minutes". See
+/// [format_time_until_from] for the units.
+#[cfg(feature = "chrono")]
+pub fn format_time_since<Tz: TimeZone>(past: DateTime<Tz>, locale: Locale) -> String {
+    format_time_since_from(past, Utc::now(), locale)
+}
+
+/// Formats the time since `past` until `now`, e.g. "5 minutes ago", like
+/// [format_time_until_from].
+#[cfg(feature = "chrono")]
+pub fn format_time_since_from<Tz: TimeZone, NowTz: TimeZone>(
+    past: DateTime<Tz>,
+    now: DateTime<NowTz>,
+    locale: Locale,
+) -> String {
+    format_relative_time(past.signed_duration_since(now), locale)
+}
+
+/// Formats `delta` as time in the future if positive or in the past if negative.
+#[cfg(feature = "chrono")]
+fn format_relative_time(delta: Duration, locale: Locale) -> String {
+    let words = RelativeTimeWords::of(locale);
+    let seconds = delta.num_seconds();
+    match format_time_amount(seconds.unsigned_abs(), words.units, locale) {
+        None => words.now.to_owned(),
+        Some(amount) if seconds > 0 => words.future.replace("{}", &amount),
+        Some(amount) => words.past.replace("{}", &amount),
+    }
+}
+
+/// Formats `seconds` in the largest unit fitting into them with the matching plural form of
+/// `units`, e.g. "2 days". Returns `None` for zero seconds.
+#[cfg(feature = "chrono")]
+fn format_time_amount(seconds: u64, units: [[&str; 3]; 4], locale: Locale) -> Option<String> {
+    let (count, unit) = [(86_400, 3), (3_600, 2), (60, 1), (1, 0)]
+        .into_iter()
+        .map(|(unit_seconds, unit)| (seconds / unit_seconds, unit))
+        .find(|&(count, _)| count > 0)?;
+    let forms = units[unit];
+    let unit = match locale.select_plural(count) {
+        PluralCategory::One => forms[0],
+        PluralCategory::Few => forms[1],
+        _ => forms[2],
+    };
+    Some(format!("{} {}", format_int(count, locale), unit))
+}
+
+/// Formats the time until a deadline `delta` ahead, phrasing passed deadlines as overdue, e.g.
+/// "overdue by 2 days" or "2 Tage überfällig".
+///
+/// Deadlines which have not passed yet are formatted like [format_time_until_from], e.g.
+/// "in 2 days", with the same units.
+#[cfg(feature = "chrono")]
+pub fn format_overdue(delta: Duration, locale: Locale) -> String {
+    let words = RelativeTimeWords::of(locale);
+    let mut units = words.units;
+    for &(unit, forms) in words.nominative_units {
+        units[unit] = forms;
+    }
+    match format_time_amount(delta.num_seconds().unsigned_abs(), units, locale) {
+        Some(amount) if delta.num_seconds() < 0 => words.overdue.replace("{}", &amount),
+        _ => format_relative_time(delta, locale),
+    }
+}
+
+/// The words used by [format_relative_time].
+#[cfg(feature = "chrono")]
+struct RelativeTimeWords {
+    now: &'static str,
+    /// Template for times in the future, `{}` is replaced with the amount.
+    future: &'static str,
+    /// Template for times in the past, `{}` is replaced with the amount.
+    past: &'static str,
+    /// The plural forms "one", "few" and "other" of seconds, minutes, hours and days in the case
+    /// used after the prepositions of `future` and `past`, e.g. the dative in German.
+    units: [[&'static str; 3]; 4],
+    /// Template for overdue times, `{}` is replaced with the amount in the nominative case.
+    overdue: &'static str,
+    /// The units whose nominative forms differ from `units`, by their index into `units`.
+    nominative_units: &'static [(usize, [&'static str; 3])],
+}
+
+#[cfg(feature = "chrono")]
+impl RelativeTimeWords {
+    fn of(locale: Locale) -> Self {
+        match locale {
+            Locale::de_DE => RelativeTimeWords {
+                now: "jetzt",
+                future: "in {}",
+                past: "vor {}",
+                units: [
+                    ["Sekunde", "Sekunden", "Sekunden"],
+                    ["Minute", "Minuten", "Minuten"],
+                    ["Stunde", "Stunden", "Stunden"],
+                    ["Tag", "Tagen", "Tagen"],
+                ],
+                overdue: "{} überfällig",
+                nominative_units: &[(3, ["Tag", "Tage", "Tage"])],
+            },
+            Locale::en_GB => RelativeTimeWords {
+                now: "now",
+                future: "in {}",
+                past: "{} ago",
+                units: [
+                    ["second", "seconds", "seconds"],
+                    ["minute", "minutes", "minutes"],
+                    ["hour", "hours", "hours"],
+                    ["day", "days", "days"],
+                ],
+                overdue: "overdue by {}",
+                nominative_units: &[],
+            },
+            Locale::es_ES => RelativeTimeWords {
+                now: "ahora",
+                future: "dentro de {}",
+                past: "hace {}",
+                units: [
+                    ["segundo", "segundos", "segundos"],
+                    ["minuto", "minutos", "minutos"],
+                    ["hora", "horas", "horas"],
+                    ["día", "días", "días"],
+                ],
+                overdue: "con {} de retraso",
+                nominative_units: &[],
+            },
+            Locale::fr_FR => RelativeTimeWords {
+                now: "maintenant",
+                future: "dans {}",
+                past: "il y a {}",
+                units: [
+                    ["seconde", "secondes", "secondes"],
+                    ["minute", "minutes", "minutes"],
+                    ["heure", "heures", "heures"],
+                    ["jour", "jours", "jours"],
+                ],
+                overdue: "en retard de {}",
+                nominative_units: &[],
+            },
+            Locale::it_IT => RelativeTimeWords {
+                now: "ora",
+                future: "tra {}",
+                past: "{} fa",
+                units: [
+                    ["secondo", "secondi", "secondi"],
+                    ["minuto", "minuti", "minuti"],
+                    ["ora", "ore", "ore"],
+                    ["giorno", "giorni", "giorni"],
+                ],
+                overdue: "in ritardo di {}",
+                nominative_units: &[],
+            },
+            Locale::pt_PT => RelativeTimeWords {
+                now: "agora",
+                future: "dentro de {}",
+                past: "há {}",
+                units: [
+                    ["segundo", "segundos", "segundos"],
+                    ["minuto", "minutos", "minutos"],
+                    ["hora", "horas", "horas"],
+                    ["dia", "dias", "dias"],
+                ],
+                overdue: "com {} de atraso",
+                nominative_units: &[],
+            },
+            Locale::ru_RU => RelativeTimeWords {
+                now: "сейчас",
+                future: "через {}",
+                past: "{} назад",
+                units: [
+                    ["секунду", "секунды", "секунд"],
+                    ["минуту", "минуты", "минут"],
+                    ["час", "часа", "часов"],
+                    ["день", "дня", "дней"],
+                ],
+                overdue: "просрочено на {}",
+                nominative_units: &[],
+            },
+        }
+    }
+}
+
+/// Formats an ISO 8601 week number, e.g. "KW 23" or "Week 23".
+pub fn format_week(week: u8, locale: Locale) -> String {
+    let prefix = match locale {
+        Locale::de_DE => "KW",
+        Locale::en_GB => "Week",
+        Locale::es_ES | Locale::pt_PT => "Semana",
+        Locale::fr_FR => "Semaine",
+        Locale::it_IT => "Settimana",
+        Locale::ru_RU => "Неделя",
+    };
+    format!("{} {}", prefix, week)
+}
+
+/// Formats the ISO 8601 week of `date` like [format_week].
+///
+/// Note that the week may belong to the previous or next year, e.g. 2021-01-01 is in week 53.
+#[cfg(feature = "chrono")]
+pub fn format_iso_week(date: NaiveDate, locale: Locale) -> String {
+    // ISO weeks are in 1..=53.
+    format_week(date.iso_week().week() as u8, locale)
+}
+
+/// Formats a date with the day as ordinal and the month name, e.g. "1st of May 2024",
+/// "1. Mai 2024" or "1er mai 2024".
+///
+/// Locales which only use an ordinal for the first day of the month, like French and Italian, use
+/// the cardinal number for all other days.
+///
+/// # Panics
+///
+/// Panics if `month` is not in `1..=12`.
+pub fn format_ordinal_date(day: u8, month: u8, year: i32, locale: Locale) -> String {
+    assert!((1..=12).contains(&month), "invalid month {}", month);
+    let month = month_names(locale)[usize::from(month - 1)];
+
+    match locale {
+        Locale::de_DE => format!("{}. {} {}", day, month, year),
+        Locale::en_GB => {
+            let suffix = match (day % 10, day % 100) {
+                (_, 11..=13) => "th",
+                (1, _) => "st",
+                (2, _) => "nd",
+                (3, _) => "rd",
+                _ => "th",
+            };
+            format!("{}{} of {} {}", day, suffix, month, year)
+        }
+        Locale::es_ES | Locale::pt_PT => format!("{} de {} de {}", day, month, year),
+        Locale::fr_FR if day == 1 => format!("1er {} {}", month, year),
+        Locale::it_IT if day == 1 => format!("1º {} {}", month, year),
+        Locale::fr_FR | Locale::it_IT | Locale::ru_RU => format!("{} {} {}", day, month, year),
+    }
+}
+
+/// Formats the range of dates from `start` to `end` and omits the month and year of `start` if
+/// they are the same as for `end`, e.g. "12.–14. Mai 2024" or "28. April – 3. Mai 2024" in
+/// German.
+///
+/// Equal dates are formatted as a single date. French and Italian use an ordinal for the first
+/// day of a month like [format_ordinal_date].
+#[cfg(feature = "chrono")]
+pub fn format_date_range(start: NaiveDate, end: NaiveDate, locale: Locale) -> String {
+    let day = |date: NaiveDate| match (locale, date.day()) {
+        (Locale::de_DE, day) => format!("{}.", day),
+        (Locale::fr_FR, 1) => "1er".to_owned(),
+        (Locale::it_IT, 1) => "1º".to_owned(),
+        (_, day) => day.to_string(),
+    };
+    let day_month = |date: NaiveDate| {
+        let month = month_names(locale)[date.month0() as usize];
+        match locale {
+            Locale::es_ES | Locale::pt_PT => format!("{} de {}", day(date), month),
+            _ => format!("{} {}", day(date), month),
+        }
+    };
+    let full = |date: NaiveDate| match locale {
+        Locale::es_ES | Locale::pt_PT => format!("{} de {}", day_month(date), date.year()),
+        _ => format!("{} {}", day_month(date), date.year()),
+    };
+
+    if start == end {
+        full(end)
+    } else if start.year() != end.year() {
+        format!("{} – {}", full(start), full(end))
+    } else if start.month() != end.month() {
+        format!("{} – {}", day_month(start), full(end))
+    } else {
+        format!("{}–{}", day(start), full(end))
+    }
+}
+
+/// Returns the names of the months as used after a day, i.e. in the genitive case for Russian.
+fn month_names(locale: Locale) -> [&'static str; 12] {
+    match locale {
+        Locale::de_DE => [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        Locale::en_GB => [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ],
+        Locale::es_ES => [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        Locale::fr_FR => [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        Locale::it_IT => [
+            "gennaio",
+            "febbraio",
+            "marzo",
+            "aprile",
+            "maggio",
+            "giugno",
+            "luglio",
+            "agosto",
+            "settembre",
+            "ottobre",
+            "novembre",
+            "dicembre",
+        ],
+        Locale::pt_PT => [
+            "janeiro",
+            "fevereiro",
+            "março",
+            "abril",
+            "maio",
+            "junho",
+            "julho",
+            "agosto",
+            "setembro",
+            "outubro",
+            "novembro",
+            "dezembro",
+        ],
+        Locale::ru_RU => [
+            "января",
+            "февраля",
+            "марта",
+            "апреля",
+            "мая",
+            "июня",
+            "июля",
+            "августа",
+            "сентября",
+            "октября",
+            "ноября",
+            "декабря",
+        ],
+    }
+}
+
+/// Returns the Russian word for days matching `plural`.
+#[cfg(feature = "chrono")]
+fn russian_days(plural: PluralCategory) -> &'static str {
+    match plural {
+        PluralCategory::One => "день",
+        PluralCategory::Few => "дня",
+        _ => "дней",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "chrono")]
+    use super::{
+        format_date_range, format_iso_week, format_overdue, format_relative_date,
+        format_time_since_from, format_time_until_from,
+    };
+    use super::{format_ordinal_date, format_week};
+    use crate::Locale;
+    #[cfg(feature = "chrono")]
+    use chrono::{Duration, NaiveDate, TimeZone, Utc};
+
+    #[test]
+    fn week() {
+        assert_eq!(format_week(23, Locale::de_DE), "KW 23");
+        assert_eq!(format_week(23, Locale::en_GB), "Week 23");
+        assert_eq!(format_week(1, Locale::ru_RU), "Неделя 1");
+    }
+
+    #[test]
+    fn ordinal_date() {
+        assert_eq!(
+            format_ordinal_date(1, 5, 2024, Locale::en_GB),
+            "1st of May 2024"
+        );
+        assert_eq!(
+            format_ordinal_date(22, 6, 2024, Locale::en_GB),
+            "22nd of June 2024"
+        );
+        assert_eq!(
+            format_ordinal_date(13, 6, 2024, Locale::en_GB),
+            "13th of June 2024"
+        );
+        assert_eq!(
+            format_ordinal_date(1, 5, 2024, Locale::de_DE),
+            "1. Mai 2024"
+        );
+        assert_eq!(
+            format_ordinal_date(1, 5, 2024, Locale::fr_FR),
+            "1er mai 2024"
+        );
+        assert_eq!(
+            format_ordinal_date(2, 12, 2024, Locale::fr_FR),
+            "2 décembre 2024"
+        );
+        assert_eq!(
+            format_ordinal_date(3, 3, 2024, Locale::ru_RU),
+            "3 марта 2024"
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn iso_week() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        assert_eq!(format_iso_week(date(2024, 6, 5), Locale::de_DE), "KW 23");
+        assert_eq!(format_iso_week(date(2021, 1, 1), Locale::en_GB), "Week 53");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn relative_date() {
+        // A Wednesday.
+        let now = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+        let format = |day, locale| {
+            format_relative_date(NaiveDate::from_ymd_opt(2024, 5, day).unwrap(), now, locale)
+        };
+
+        assert_eq!(format(15, Locale::de_DE), "heute");
+        assert_eq!(format(14, Locale::de_DE), "gestern");
+        assert_eq!(format(16, Locale::de_DE), "morgen");
+        assert_eq!(format(12, Locale::de_DE), "vor 3 Tagen");
+        assert_eq!(format(21, Locale::de_DE), "in 6 Tagen");
+        assert_eq!(format(8, Locale::de_DE), "letzte Woche");
+        assert_eq!(format(6, Locale::de_DE), "letzte Woche");
+        assert_eq!(format(5, Locale::de_DE), "05.05.2024");
+        assert_eq!(format(22, Locale::de_DE), "nächste Woche");
+        assert_eq!(format(27, Locale::de_DE), "27.05.2024");
+
+        assert_eq!(format(14, Locale::en_GB), "yesterday");
+        assert_eq!(format(13, Locale::en_GB), "2 days ago");
+        assert_eq!(format(1, Locale::en_GB), "01/05/2024");
+        assert_eq!(format(10, Locale::ru_RU), "5 дней назад");
+        assert_eq!(format(17, Locale::ru_RU), "через 2 дня");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn relative_time() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 15, 12, 0, 0).unwrap();
+        let until = |delta, locale| format_time_until_from(now + delta, now, locale);
+        let since = |delta, locale| format_time_since_from(now - delta, now, locale);
+
+        assert_eq!(until(Duration::minutes(5), Locale::en_GB), "in 5 minutes");
+        assert_eq!(until(Duration::minutes(90), Locale::en_GB), "in 1 hour");
+        assert_eq!(since(Duration::minutes(5), Locale::en_GB), "5 minutes ago");
+        assert_eq!(since(Duration::days(1), Locale::de_DE), "vor 1 Tag");
+        assert_eq!(since(Duration::days(3), Locale::de_DE), "vor 3 Tagen");
+        assert_eq!(
+            until(Duration::seconds(30), Locale::fr_FR),
+            "dans 30 secondes"
+        );
+        assert_eq!(
+            until(Duration::minutes(21), Locale::ru_RU),
+            "через 21 минуту"
+        );
+        assert_eq!(since(Duration::hours(3), Locale::ru_RU), "3 часа назад");
+        assert_eq!(until(Duration::milliseconds(500), Locale::en_GB), "now");
+
+        // Past targets and future pasts swap the direction.
+        assert_eq!(until(Duration::minutes(-5), Locale::en_GB), "5 minutes ago");
+        assert_eq!(since(Duration::minutes(-5), Locale::en_GB), "in 5 minutes");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn overdue() {
+        assert_eq!(
+            format_overdue(Duration::days(-2), Locale::en_GB),
+            "overdue by 2 days"
+        );
+        assert_eq!(
+            format_overdue(Duration::days(-2), Locale::de_DE),
+            "2 Tage überfällig"
+        );
+        assert_eq!(
+            format_overdue(Duration::days(-1), Locale::de_DE),
+            "1 Tag überfällig"
+        );
+        assert_eq!(
+            format_overdue(Duration::hours(-5), Locale::ru_RU),
+            "просрочено на 5 часов"
+        );
+        assert_eq!(
+            format_overdue(Duration::days(2), Locale::de_DE),
+            "in 2 Tagen"
+        );
+        assert_eq!(format_overdue(Duration::zero(), Locale::en_GB), "now");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn date_range() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+
+        assert_eq!(
+            format_date_range(date(2024, 5, 12), date(2024, 5, 14), Locale::de_DE),
+            "12.–14. Mai 2024"
+        );
+        assert_eq!(
+            format_date_range(date(2024, 4, 28), date(2024, 5, 3), Locale::de_DE),
+            "28. April – 3. Mai 2024"
+        );
+        assert_eq!(
+            format_date_range(date(2024, 12, 28), date(2025, 1, 3), Locale::de_DE),
+            "28. Dezember 2024 – 3. Januar 2025"
+        );
+        assert_eq!(
+            format_date_range(date(2024, 5, 12), date(2024, 5, 12), Locale::de_DE),
+            "12. Mai 2024"
+        );
+        assert_eq!(
+            format_date_range(date(2024, 5, 12), date(2024, 5, 14), Locale::en_GB),
+            "12–14 May 2024"
+        );
+        assert_eq!(
+            format_date_range(date(2024, 4, 28), date(2024, 5, 3), Locale::es_ES),
+            "28 de abril – 3 de mayo de 2024"
+        );
+        assert_eq!(
+            format_date_range(date(2024, 5, 1), date(2024, 5, 3), Locale::fr_FR),
+            "1er–3 mai 2024"
+        );
+    }
+}
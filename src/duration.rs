@@ -0,0 +1,70 @@
+//! Localized formatting of durations.
+
+use crate::{format_int, Locale};
+use std::time::Duration;
+
+/// Formats `duration` compactly with abbreviated units, e.g. `2 Std. 5 Min.` in German or
+/// `2 h 5 min` in English.
+///
+/// The duration is split into days, hours, minutes and seconds, dropping fractions of seconds.
+/// Components which are zero are omitted, a duration shorter than a second is `0 s`. Numbers and
+/// units are separated by no-break spaces.
+pub fn format_duration_abbrev(duration: Duration, locale: Locale) -> String {
+    let units = abbreviations(locale);
+    let seconds = duration.as_secs();
+    let components = [
+        seconds / 86_400,
+        seconds / 3_600 % 24,
+        seconds / 60 % 60,
+        seconds % 60,
+    ];
+
+    let formatted: Vec<String> = components
+        .iter()
+        .zip(units)
+        .filter(|(&count, _)| count > 0)
+        .map(|(&count, unit)| format!("{}\u{a0}{}", format_int(count, locale), unit))
+        .collect();
+    if formatted.is_empty() {
+        format!("0\u{a0}{}", units[3])
+    } else {
+        formatted.join(" ")
+    }
+}
+
+/// Returns the abbreviations of days, hours, minutes and seconds.
+fn abbreviations(locale: Locale) -> [&'static str; 4] {
+    match locale {
+        Locale::de_DE => ["Tg.", "Std.", "Min.", "Sek."],
+        Locale::en_GB | Locale::es_ES | Locale::pt_PT => ["d", "h", "min", "s"],
+        Locale::fr_FR => ["j", "h", "min", "s"],
+        Locale::it_IT => ["g", "h", "min", "s"],
+        Locale::ru_RU => ["д", "ч", "мин", "с"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_duration_abbrev;
+    use crate::Locale;
+    use std::time::Duration;
+
+    #[test]
+    fn duration_abbrev() {
+        let format = |seconds, locale| format_duration_abbrev(Duration::from_secs(seconds), locale);
+
+        assert_eq!(format(7_500, Locale::de_DE), "2\u{a0}Std. 5\u{a0}Min.");
+        assert_eq!(format(7_500, Locale::en_GB), "2\u{a0}h 5\u{a0}min");
+        assert_eq!(format(7_205, Locale::en_GB), "2\u{a0}h 5\u{a0}s");
+        assert_eq!(format(180, Locale::en_GB), "3\u{a0}min");
+        assert_eq!(
+            format(90_061, Locale::de_DE),
+            "1\u{a0}Tg. 1\u{a0}Std. 1\u{a0}Min. 1\u{a0}Sek."
+        );
+        assert_eq!(format(0, Locale::en_GB), "0\u{a0}s");
+        assert_eq!(
+            format_duration_abbrev(Duration::from_millis(2_500), Locale::ru_RU),
+            "2\u{a0}с"
+        );
+    }
+}
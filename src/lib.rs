@@ -48,68 +48,272 @@
 //! # Features
 //!
 //! - `build`: adds functions which can be used in `build.rs` to automate the gettext workflow.
-//! - `chrono`: implements `From<getprose::Locale>` for `chrono::Locale`.
+//! - `chrono`: implements `From<getprose::Locale>` for `chrono::Locale` and adds functions to format
+//!   dates like `format_relative_date`.
+//! - `include_dir`: adds `Localizer::from_include_dir` to load catalogs embedded with
+//!   `include_dir!`.
+//! - `notify`: adds `Localizer::watch_mo_dir` to reload catalogs when their MO files change.
+//! - `serde_json`: adds `export_mo_json` to export catalogs as JSON for frontends.
+//! - `test_support`: adds the module `test_support` to build catalogs in unit tests without MO
+//!   files.
+//! - `unic-langid`: implements conversions between `getprose::Locale` and
+//!   `unic_langid::LanguageIdentifier`.
 
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(missing_docs)]
 
 #[cfg(feature = "build")]
 pub mod build;
+mod collation;
+mod coverage;
+mod currency;
+mod date;
+mod duration;
+mod locale_data;
+mod metadata;
+mod mo;
+mod number;
+mod plural;
+mod po;
+#[cfg(any(test, feature = "test_support"))]
+pub mod test_support;
+mod text;
+mod translator;
+#[cfg(feature = "notify")]
+mod watch;
+
+pub use collation::{sort_strings, SortKey};
+pub use coverage::CoverageError;
+pub use currency::{
+    format_currency, format_currency_accounting, format_currency_default, Currency, CurrencyDisplay,
+};
+#[cfg(feature = "chrono")]
+pub use date::{
+    format_date_range, format_iso_week, format_overdue, format_relative_date, format_time_since,
+    format_time_since_from, format_time_until, format_time_until_from,
+};
+pub use date::{format_ordinal_date, format_week};
+pub use duration::format_duration_abbrev;
+pub use metadata::CatalogMetadata;
+#[cfg(feature = "serde_json")]
+pub use mo::export_mo_json;
+pub use mo::MoDirError;
+pub use number::{
+    format_count_compact, format_engineering, format_f64_aligned, format_percent_delta,
+    format_percentage_points, format_rate, format_rating, format_rounded, format_scientific,
+    ExponentStyle, RateUnit, RatingStyle, ZeroSign,
+};
+pub use plural::{parse_plural_forms, ParsePluralFormsError, PluralCategory, PluralRule};
+pub use po::{escape_po_string, unescape_po_string};
+pub use text::{format_counts, format_list_truncated, format_tristate, Tristate};
+pub use translator::Translator;
+#[cfg(feature = "notify")]
+pub use watch::{WatchError, WatchHandle};
 
 use dynfmt::curly::SimpleCurlyFormat;
-use dynfmt::{Error as DynFmtError, Format};
+use dynfmt::{Argument, Error as DynFmtError, Format, FormatArgs};
 use format_num::format_num;
 use gettext::Catalog;
+use locale_data::ALL_LOCALES;
 use num_format::ToFormattedString;
 use std::borrow;
 use std::collections::HashMap;
+use std::{io, ptr};
 use thiserror::Error;
 
+/// Lookup of translations as implemented by [Catalog].
+///
+/// This allows to use a [Localizer] with other catalogs, e.g. fake catalogs in tests.
+pub trait CatalogLike {
+    /// Translates a singular string, see [Catalog::gettext].
+    fn gettext<'a>(&'a self, msg_id: &'a str) -> &'a str;
+
+    /// Translates a string depending on `n`, see [Catalog::ngettext].
+    fn ngettext<'a>(&'a self, msg_id: &'a str, msg_id_plural: &'a str, n: u64) -> &'a str;
+
+    /// Translates a singular string in `msg_context`, see [Catalog::pgettext].
+    fn pgettext<'a>(&'a self, msg_context: &'a str, msg_id: &'a str) -> &'a str;
+
+    /// Translates a string depending on `n` in `msg_context`, see [Catalog::npgettext].
+    fn npgettext<'a>(
+        &'a self,
+        msg_context: &'a str,
+        msg_id: &'a str,
+        msg_id_plural: &'a str,
+        n: u64,
+    ) -> &'a str;
+}
+
+impl CatalogLike for Catalog {
+    fn gettext<'a>(&'a self, msg_id: &'a str) -> &'a str {
+        Catalog::gettext(self, msg_id)
+    }
+
+    fn ngettext<'a>(&'a self, msg_id: &'a str, msg_id_plural: &'a str, n: u64) -> &'a str {
+        Catalog::ngettext(self, msg_id, msg_id_plural, n)
+    }
+
+    fn pgettext<'a>(&'a self, msg_context: &'a str, msg_id: &'a str) -> &'a str {
+        Catalog::pgettext(self, msg_context, msg_id)
+    }
+
+    fn npgettext<'a>(
+        &'a self,
+        msg_context: &'a str,
+        msg_id: &'a str,
+        msg_id_plural: &'a str,
+        n: u64,
+    ) -> &'a str {
+        Catalog::npgettext(self, msg_context, msg_id, msg_id_plural, n)
+    }
+}
+
 /// Helper struct to handle initialization of and access to translations.
-pub struct Localizer {
-    catalogs: HashMap<Locale, Catalog>,
+///
+/// Uses gettext [Catalog]s by default, but works with any [CatalogLike].
+pub struct Localizer<C = Catalog> {
+    catalogs: HashMap<Locale, C>,
     /// Fallback locale which can be assumed to be contained in catalogs.
     fallback: Locale,
+    /// Ordered fallback locales tried before `fallback`, empty if there is a single one.
+    fallbacks: Vec<Locale>,
 }
 
-impl Localizer {
+impl<C> Localizer<C> {
     /// Creates a new `Localizer` with the given fallback locale.
     ///
     /// Fails with [`MissingFallbackError`] if `fallback` is missing in `catalogs`.
     pub fn new(
-        catalogs: HashMap<Locale, Catalog>,
+        catalogs: HashMap<Locale, C>,
         fallback: Locale,
     ) -> Result<Self, MissingFallbackError> {
         if !catalogs.contains_key(&fallback) {
             return Err(MissingFallbackError(fallback));
         }
-        Ok(Self { catalogs, fallback })
+        Ok(Self {
+            catalogs,
+            fallback,
+            fallbacks: Vec::new(),
+        })
+    }
+
+    /// Creates a new `Localizer` with the fallback locale read from the `GETPROSE_FALLBACK`
+    /// environment variable, e.g. `de_DE`.
+    ///
+    /// Fails with [`FallbackFromEnvError`] if the variable is unset, cannot be parsed as
+    /// [Locale] or the fallback is missing in `catalogs`.
+    pub fn new_from_env(catalogs: HashMap<Locale, C>) -> Result<Self, FallbackFromEnvError> {
+        Self::new_from_var(catalogs, std::env::var("GETPROSE_FALLBACK").ok())
+    }
+
+    /// Implements [Localizer::new_from_env] with the `value` of the environment variable.
+    fn new_from_var(
+        catalogs: HashMap<Locale, C>,
+        value: Option<String>,
+    ) -> Result<Self, FallbackFromEnvError> {
+        let value = value.ok_or(FallbackFromEnvError::Unset)?;
+        let fallback = value
+            .parse()
+            .map_err(|_| FallbackFromEnvError::Invalid(value))?;
+        Self::new(catalogs, fallback).map_err(|_| FallbackFromEnvError::Missing(fallback))
+    }
+
+    /// Creates a new `Localizer` with an ordered list of fallback locales.
+    ///
+    /// Requested locales without a catalog fall back to the first locale in `fallbacks` which has
+    /// a catalog. The whole list is kept, so a catalog added later by [Localizer::merge] for an
+    /// earlier locale in `fallbacks` takes precedence. Fails with [`MissingFallbacksError`] if
+    /// none of `fallbacks` are in `catalogs`.
+    pub fn new_with_fallbacks(
+        catalogs: HashMap<Locale, C>,
+        fallbacks: Vec<Locale>,
+    ) -> Result<Self, MissingFallbacksError> {
+        match fallbacks
+            .iter()
+            .find(|fallback| catalogs.contains_key(fallback))
+        {
+            Some(&fallback) => Ok(Self {
+                catalogs,
+                fallback,
+                fallbacks,
+            }),
+            None => Err(MissingFallbacksError(fallbacks)),
+        }
     }
 
     /// Get the locale currently used as fallback.
+    ///
+    /// With several fallbacks this is the first one which has a catalog.
     pub fn fallback(&self) -> Locale {
-        self.fallback
+        self.fallbacks
+            .iter()
+            .copied()
+            .find(|fallback| self.catalogs.contains_key(fallback))
+            .unwrap_or(self.fallback)
     }
 
-    /// Returns the catalog for `locale` or the catalog of the fallback locale.
-    pub fn get_catalog(&self, locale: impl Into<Locale>) -> &Catalog {
-        let locale = locale.into();
+    /// Returns the locale whose catalog [get_catalog](Localizer::get_catalog) returns for
+    /// `requested`, i.e. `requested` if it has a catalog or the fallback locale otherwise.
+    pub fn resolve(&self, requested: impl Into<Locale>) -> Locale {
+        let locale = requested.into();
 
         if self.catalogs.contains_key(&locale) {
-            self.catalogs.get(&locale).unwrap_or_else(|| {
-                panic!("Unreachable: Could not get translation for {:?}", &locale)
-            })
+            locale
         } else {
             // Get the fallback locale instead.
-            self.catalogs
-                .get(&self.fallback)
-                .expect("Unreachable: Missing catalog for fallback locale")
+            self.fallback()
+        }
+    }
+
+    /// Returns the catalog for `locale` or the catalog of the fallback locale.
+    pub fn get_catalog(&self, locale: impl Into<Locale>) -> &C {
+        let locale = self.resolve(locale);
+
+        self.catalogs
+            .get(&locale)
+            .unwrap_or_else(|| panic!("Unreachable: Could not get translation for {:?}", &locale))
+    }
+
+    /// Returns the catalog for `locale` or, if it is missing, the catalog of the locale chosen by
+    /// `fallback_fn`, e.g. to log missing locales or to decide based on the request.
+    ///
+    /// `fallback_fn` is only called for missing locales. If the locale it returns is missing as
+    /// well, the catalog of the fallback locale is returned.
+    pub fn get_catalog_with(
+        &self,
+        locale: impl Into<Locale>,
+        fallback_fn: impl Fn(Locale) -> Locale,
+    ) -> &C {
+        let locale = locale.into();
+        match self.catalogs.get(&locale) {
+            Some(catalog) => catalog,
+            None => self.get_catalog(fallback_fn(locale)),
         }
     }
 
+    /// Returns the catalog for the locale code `s`, e.g. from an HTTP header, or the catalog of the
+    /// fallback locale if `s` is no supported locale.
+    ///
+    /// `s` is parsed with [FromStr](std::str::FromStr), so hyphens and any case are accepted, e.g.
+    /// `de-DE` or `DE`. Unsupported regions fall back to their language, e.g. `de-AT` to
+    /// [Locale::de_DE].
+    pub fn get_catalog_str(&self, s: &str) -> &C {
+        self.get_catalog(parse_locale_lenient(s).unwrap_or(self.fallback()))
+    }
+
+    /// Returns the best match among the loaded locales for the value of an HTTP `Accept-Language`
+    /// header, or the fallback locale if none matches.
+    ///
+    /// See [negotiate] for how the header is matched.
+    pub fn negotiate(&self, accept_language: &str) -> Locale {
+        let available: Vec<Locale> = self.catalogs.keys().copied().collect();
+        negotiate(accept_language, &available).unwrap_or(self.fallback())
+    }
+
     /// Gets the catalog for the fallback locale.
-    pub fn get_fallback(&self) -> (Locale, &Catalog) {
-        (self.fallback, self.get_catalog(self.fallback))
+    pub fn get_fallback(&self) -> (Locale, &C) {
+        let fallback = self.fallback();
+        (fallback, self.get_catalog(fallback))
     }
 
     /// Returns an iterator over all added [Locale]s.
@@ -121,6 +325,150 @@ impl Localizer {
     pub fn contains(&self, locale: impl Into<Locale>) -> bool {
         self.catalogs.contains_key(&locale.into())
     }
+
+    /// Adds the catalogs of `other` to `self`, e.g. to combine the translations of an application
+    /// and a plugin. The fallbacks of `self` are kept.
+    ///
+    /// Catalogs cannot be combined message by message, so a conflict arises for each locale with a
+    /// catalog in both `self` and `other` and is resolved by `on_conflict`. With
+    /// [ConflictPolicy::Error] `self` is left unchanged if there are conflicts.
+    pub fn merge(
+        &mut self,
+        other: Localizer<C>,
+        on_conflict: ConflictPolicy,
+    ) -> Result<(), MergeConflictError> {
+        if on_conflict == ConflictPolicy::Error {
+            let mut conflicts: Vec<Locale> = other
+                .catalogs
+                .keys()
+                .filter(|locale| self.catalogs.contains_key(locale))
+                .copied()
+                .collect();
+            if !conflicts.is_empty() {
+                conflicts.sort_by_key(|locale| locale.as_str());
+                return Err(MergeConflictError(conflicts));
+            }
+        }
+
+        for (locale, catalog) in other.catalogs {
+            if on_conflict == ConflictPolicy::TakeOther || !self.catalogs.contains_key(&locale) {
+                self.catalogs.insert(locale, catalog);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Decides which catalog is used by [Localizer::merge] if both localizers contain one for a
+/// locale.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConflictPolicy {
+    /// Keep the catalog of the merged-into localizer.
+    KeepSelf,
+    /// Replace the catalog with the one of the merged localizer.
+    TakeOther,
+    /// Fail with a [MergeConflictError].
+    Error,
+}
+
+/// An error signalling that both merged localizers contain catalogs for the same locales.
+#[derive(Clone, Debug, Error)]
+#[error("Translations for the locales {0:?} are contained in both localizers.")]
+pub struct MergeConflictError(pub Vec<Locale>);
+
+impl<C: CatalogLike> Localizer<C> {
+    /// Compares the `nplurals` declared in each catalog's `Plural-Forms` header against
+    /// [Locale::plural_form_count].
+    ///
+    /// Catalogs without a `Plural-Forms` header are skipped, since gettext falls back to its
+    /// default rule for them.
+    pub fn check_plural_forms(&self) -> Vec<PluralFormWarning> {
+        self.catalogs
+            .iter()
+            .filter_map(|(&locale, catalog)| {
+                let declared = declared_plural_form_count(catalog)?;
+                let expected = locale.plural_form_count();
+                (declared != expected).then_some(PluralFormWarning {
+                    locale,
+                    expected,
+                    declared,
+                })
+            })
+            .collect()
+    }
+
+    /// Checks the plural translations of `msgids` in the catalog of `locale` and lists those which
+    /// lack some of the [Locale::plural_form_count] forms, e.g. the "many" form in Russian.
+    ///
+    /// Each entry of `msgids` is a pair of msgid and msgid_plural. They have to be passed in since
+    /// catalogs cannot be enumerated. Untranslated msgids and locales without catalog are skipped.
+    pub fn check_plural_completeness(
+        &self,
+        locale: Locale,
+        msgids: &[(&str, &str)],
+    ) -> Vec<IncompletePlural> {
+        // The smallest numbers using each plural form of the supported locales.
+        const SAMPLES: [u64; 3] = [1, 2, 5];
+
+        let Some(catalog) = self.catalogs.get(&locale) else {
+            return Vec::new();
+        };
+        let expected = locale.plural_form_count();
+        msgids
+            .iter()
+            .filter_map(|&(msgid, msgid_plural)| {
+                // gettext returns the passed msgid or msgid_plural itself for missing forms.
+                let translated = SAMPLES[..expected]
+                    .iter()
+                    .filter(|&&n| {
+                        let s = catalog.ngettext(msgid, msgid_plural, n);
+                        !ptr::eq(s, msgid) && !ptr::eq(s, msgid_plural)
+                    })
+                    .count();
+                (translated > 0 && translated < expected).then(|| IncompletePlural {
+                    msgid: msgid.to_owned(),
+                    expected,
+                    translated,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A plural translation found by [Localizer::check_plural_completeness] which lacks some forms.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IncompletePlural {
+    /// The msgid of the translation.
+    pub msgid: String,
+    /// The number of plural forms expected for the locale.
+    pub expected: usize,
+    /// The number of plural forms which are translated.
+    pub translated: usize,
+}
+
+/// Reads `nplurals` from the `Plural-Forms` header of `catalog` if there is one.
+fn declared_plural_form_count(catalog: &impl CatalogLike) -> Option<usize> {
+    // gettext returns the header block as the translation of the empty msgid.
+    let plural_forms = catalog
+        .gettext("")
+        .lines()
+        .find_map(|line| line.strip_prefix("Plural-Forms:"))?;
+
+    plural_forms
+        .split(';')
+        .find_map(|part| part.trim().strip_prefix("nplurals="))
+        .and_then(|n| n.trim().parse().ok())
+}
+
+/// A catalog declares a different number of plural forms than its locale requires.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PluralFormWarning {
+    /// The locale of the offending catalog.
+    pub locale: Locale,
+    /// The number of plural forms expected for `locale`.
+    pub expected: usize,
+    /// The number of plural forms declared in the catalog's header.
+    pub declared: usize,
 }
 
 /// An error signalling that translations for a fallback locale are missing.
@@ -128,6 +476,26 @@ impl Localizer {
 #[error("Fallback translations for locale {0:?} are missing.")]
 pub struct MissingFallbackError(Locale);
 
+/// An error signalling that the fallback locale could not be read from the environment, see
+/// [Localizer::new_from_env].
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum FallbackFromEnvError {
+    /// The `GETPROSE_FALLBACK` environment variable is not set.
+    #[error("The environment variable GETPROSE_FALLBACK for the fallback locale is not set.")]
+    Unset,
+    /// The `GETPROSE_FALLBACK` environment variable contains no supported locale.
+    #[error("The environment variable GETPROSE_FALLBACK contains the unknown locale {0:?}.")]
+    Invalid(String),
+    /// The translations for the fallback locale are missing.
+    #[error("Fallback translations for locale {0:?} from GETPROSE_FALLBACK are missing.")]
+    Missing(Locale),
+}
+
+/// An error signalling that translations for all fallback locales are missing.
+#[derive(Clone, Debug, Error)]
+#[error("Fallback translations for all locales {0:?} are missing.")]
+pub struct MissingFallbacksError(pub Vec<Locale>);
+
 /// The supported locales and central part of the localization.
 ///
 /// See module-level documentation for more information on how to use this to localize strings.
@@ -161,34 +529,187 @@ impl<'a> Locale {
             .get(self)
             .unwrap_or_else(|| panic!("Could not find translations for locale {:?}", self))
     }
+
+    /// Returns the canonical code of the [Locale], e.g. `"de_DE"`.
+    pub fn as_str(&self) -> &'static str {
+        self.data().code
+    }
+
+    /// Returns a key of the [Locale] which is stable across all versions of this crate, e.g. to
+    /// persist caches keyed by locale.
+    ///
+    /// Unlike the derived `Hash` implementation, which may change if variants are reordered or
+    /// added, the key of a [Locale] is guaranteed to never change. It is the canonical code
+    /// returned by [Locale::as_str], e.g. `"de_DE"`.
+    pub fn stable_key(&self) -> &'static str {
+        self.as_str()
+    }
+
+    /// Detects the locale from the environment like gettext does.
+    ///
+    /// The colon-separated priority list in `LANGUAGE` is checked first, then `LC_ALL`,
+    /// `LC_MESSAGES` and `LANG`. The first supported locale is returned. Charsets and modifiers
+    /// like in `de_DE.UTF-8@euro` are ignored and unsupported regions fall back to their language,
+    /// e.g. `de_AT` is detected as [Locale::de_DE].
+    pub fn from_environment() -> Option<Locale> {
+        Self::from_env_vars(|var| std::env::var(var).ok())
+    }
+
+    /// Implements [Locale::from_environment] reading environment variables with `var`.
+    fn from_env_vars(var: impl Fn(&str) -> Option<String>) -> Option<Locale> {
+        let parse = |value: &str| {
+            let value = value.split(['.', '@']).next().unwrap_or_default();
+            value.parse().ok().or_else(|| {
+                value
+                    .split_once('_')
+                    .and_then(|(language, _)| language.parse().ok())
+            })
+        };
+
+        let language = var("LANGUAGE").and_then(|language| language.split(':').find_map(parse));
+        language.or_else(|| {
+            ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .iter()
+                .filter_map(|name| var(name))
+                .find(|value| !value.is_empty())
+                .and_then(|value| parse(&value))
+        })
+    }
+
+    /// Returns a [LocaleFormatter] for the [Locale], e.g. to be used as `&dyn LocaleFormatter`.
+    pub fn as_formatter(&self) -> impl LocaleFormatter {
+        *self
+    }
+
+    /// Returns the BCP 47 language tag of the [Locale] for HTML `lang` attributes, e.g. `"de-DE"`.
+    pub fn html_lang(&self) -> &'static str {
+        self.data().html_lang
+    }
+
+    /// Returns the POSIX locale name of the [Locale] with the UTF-8 charset, e.g. `"de_DE.UTF-8"`,
+    /// for `LC_*` environment variables of external programs.
+    pub fn to_posix(&self) -> &'static str {
+        self.data().posix
+    }
+
+    /// Returns the ISO 15924 code of the script of the [Locale], e.g. `"Latn"` or `"Cyrl"` for
+    /// Russian, to select fonts.
+    pub fn script(&self) -> &'static str {
+        self.data().script
+    }
+
+    /// Checks whether the [Locale] is written from right to left.
+    ///
+    /// All currently supported locales are written from left to right.
+    pub fn is_rtl(&self) -> bool {
+        is_rtl_script(self.script())
+    }
+
+    /// Returns the canonical [Locale] of the language of the [Locale], e.g. for catalogs keyed by
+    /// language only.
+    ///
+    /// The canonical variants are [Locale::de_DE], [Locale::en_GB], [Locale::es_ES],
+    /// [Locale::fr_FR], [Locale::it_IT], [Locale::pt_PT] and [Locale::ru_RU]. As only one variant
+    /// per language is supported yet, every [Locale] is its own base.
+    pub fn to_language_base(&self) -> Locale {
+        match self {
+            Locale::de_DE => Locale::de_DE,
+            Locale::en_GB => Locale::en_GB,
+            Locale::es_ES => Locale::es_ES,
+            Locale::fr_FR => Locale::fr_FR,
+            Locale::it_IT => Locale::it_IT,
+            Locale::pt_PT => Locale::pt_PT,
+            Locale::ru_RU => Locale::ru_RU,
+        }
+    }
+
+    /// Returns the [num_format::Locale] used to format numbers in the [Locale].
+    ///
+    /// This is the same as the `From` conversion, e.g. for using [num_format] directly.
+    pub fn num_format_locale(&self) -> num_format::Locale {
+        self.data().num_format
+    }
+
+    /// Returns the number of plural forms gettext uses for the [Locale].
+    ///
+    /// This is the `nplurals` value a PO file for the locale should declare in its
+    /// `Plural-Forms` header.
+    pub fn plural_form_count(&self) -> usize {
+        self.data().plural_forms
+    }
+
+    /// Returns the placeholder used for missing data in the [Locale], e.g. "N/A" in English.
+    pub fn not_available(&self) -> &'static str {
+        self.data().not_available
+    }
+}
+
+impl AsRef<str> for Locale {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<Locale> for &'static str {
+    fn from(locale: Locale) -> Self {
+        locale.as_str()
+    }
 }
 
 impl From<Locale> for num_format::Locale {
     fn from(locale: Locale) -> Self {
-        match locale {
-            Locale::de_DE => num_format::Locale::de,
-            Locale::en_GB => num_format::Locale::en_GB,
-            Locale::es_ES => num_format::Locale::es,
-            Locale::fr_FR => num_format::Locale::fr,
-            Locale::it_IT => num_format::Locale::it,
-            Locale::pt_PT => num_format::Locale::pt,
-            Locale::ru_RU => num_format::Locale::ru,
-        }
+        locale.data().num_format
+    }
+}
+
+impl From<&Locale> for num_format::Locale {
+    fn from(locale: &Locale) -> Self {
+        (*locale).into()
     }
 }
 
 #[cfg(feature = "chrono")]
 impl From<Locale> for chrono::Locale {
     fn from(locale: Locale) -> Self {
-        match locale {
-            Locale::de_DE => chrono::Locale::de_DE,
-            Locale::en_GB => chrono::Locale::en_GB,
-            Locale::es_ES => chrono::Locale::es_ES,
-            Locale::fr_FR => chrono::Locale::fr_FR,
-            Locale::it_IT => chrono::Locale::it_IT,
-            Locale::pt_PT => chrono::Locale::pt_PT,
-            Locale::ru_RU => chrono::Locale::ru_RU,
-        }
+        locale.data().chrono
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<&Locale> for chrono::Locale {
+    fn from(locale: &Locale) -> Self {
+        (*locale).into()
+    }
+}
+
+#[cfg(feature = "unic-langid")]
+impl From<Locale> for unic_langid::LanguageIdentifier {
+    fn from(locale: Locale) -> Self {
+        locale
+            .html_lang()
+            .parse()
+            .expect("the language tags of all locales are valid")
+    }
+}
+
+#[cfg(feature = "unic-langid")]
+impl From<&Locale> for unic_langid::LanguageIdentifier {
+    fn from(locale: &Locale) -> Self {
+        (*locale).into()
+    }
+}
+
+#[cfg(feature = "unic-langid")]
+impl TryFrom<unic_langid::LanguageIdentifier> for Locale {
+    type Error = UnknownLocaleError;
+
+    /// Converts the language identifier if its BCP 47 tag matches a locale exactly, e.g. `de-DE`.
+    fn try_from(langid: unic_langid::LanguageIdentifier) -> Result<Self, Self::Error> {
+        let tag = langid.to_string();
+        ALL_LOCALES
+            .into_iter()
+            .find(|locale| locale.html_lang() == tag)
+            .ok_or(UnknownLocaleError(tag))
     }
 }
 
@@ -196,19 +717,56 @@ impl std::str::FromStr for Locale {
     type Err = UnknownLocaleError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "de_DE" | "de" => Ok(Locale::de_DE),
-            "en_GB" | "en" => Ok(Locale::en_GB),
-            "es_ES" | "es" => Ok(Locale::es_ES),
-            "fr_FR" | "fr" => Ok(Locale::fr_FR),
-            "it_IT" | "it" => Ok(Locale::it_IT),
-            "pt_PT" | "pt" => Ok(Locale::pt_PT),
-            "ru_RU" | "ru" => Ok(Locale::ru_RU),
-            _ => Err(UnknownLocaleError(s.to_owned())),
-        }
+        let normalized = s.replace('-', "_");
+        locale_data::LOCALE_DATA
+            .iter()
+            .find(|data| {
+                data.code.eq_ignore_ascii_case(&normalized)
+                    || data.language.eq_ignore_ascii_case(&normalized)
+            })
+            .map(|data| data.locale)
+            .ok_or_else(|| UnknownLocaleError(s.to_owned()))
     }
 }
 
+/// Parses `s` like [FromStr](std::str::FromStr) but falls back to the language if the region is
+/// not supported, e.g. `de-AT` to [Locale::de_DE].
+fn parse_locale_lenient(s: &str) -> Option<Locale> {
+    s.parse().ok().or_else(|| {
+        s.split(['-', '_'])
+            .next()
+            .and_then(|language| language.parse().ok())
+    })
+}
+
+/// Returns the best match among `available` for the value of an HTTP `Accept-Language` header,
+/// e.g. `"fr-CH, fr;q=0.9, en;q=0.8"`.
+///
+/// Languages are tried in order of their quality values. Unsupported regions match their language,
+/// e.g. `de-AT` matches [Locale::de_DE]. Wildcards and languages with a quality of `0` are
+/// ignored. Returns `None` if no language of the header is available.
+pub fn negotiate(accept_language: &str, available: &[Locale]) -> Option<Locale> {
+    let mut languages: Vec<(&str, f32)> = accept_language
+        .split(',')
+        .filter_map(|language| {
+            let mut parts = language.split(';');
+            let tag = parts.next()?.trim();
+            let quality = match parts.find_map(|part| part.trim().strip_prefix("q=")) {
+                Some(quality) => quality.trim().parse().ok()?,
+                None => 1.0,
+            };
+            (quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+    // The sort is stable, so languages with the same quality keep their order.
+    languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    languages
+        .into_iter()
+        .filter_map(|(tag, _)| parse_locale_lenient(tag))
+        .find(|locale| available.contains(locale))
+}
+
 /// Received an unknown locale.
 #[derive(Debug, Error, Clone)]
 #[error("Unknown locale {0}")]
@@ -217,18 +775,47 @@ pub struct UnknownLocaleError(pub String);
 /// Format `&str` during runtime.
 pub struct FormatBuilder<'a> {
     /// The template defining the formatting.
-    tpl: &'a str,
+    tpl: borrow::Cow<'a, str>,
     /// The arguments used in formatting.
     args: HashMap<&'a str, String>,
+    /// The arguments used for positional placeholders like `{0}`.
+    indexed_args: HashMap<usize, String>,
+    /// Whether arguments are wrapped in directional isolates.
+    isolate_args: bool,
 }
 
 impl<'a> FormatBuilder<'a> {
     /// Adds an argument to be used in formatting.
+    ///
+    /// `value` is converted to a `String` right away. Use [arg_string](FormatBuilder::arg_string)
+    /// to pass an owned `String` without copying it.
     pub fn arg<S: ToString>(&mut self, key: &'a str, value: &S) -> &mut Self {
         self.args.insert(key, value.to_string());
         self
     }
 
+    /// Adds an owned `String` as argument to be used in formatting without copying it.
+    pub fn arg_string(&mut self, key: &'a str, value: String) -> &mut Self {
+        self.args.insert(key, value);
+        self
+    }
+
+    /// Adds an argument for both the named placeholder `{name}` and the positional placeholder
+    /// `{index}`, e.g. `{0}`.
+    ///
+    /// This fills the slot regardless of which form a translator used in the template.
+    pub fn arg_aliased<S: ToString>(
+        &mut self,
+        name: &'a str,
+        index: usize,
+        value: &S,
+    ) -> &mut Self {
+        let value = value.to_string();
+        self.indexed_args.insert(index, value.clone());
+        self.args.insert(name, value);
+        self
+    }
+
     /// Adds all arguments contained in `args` to `self.args`.
     pub fn args<S: ToString>(&mut self, args: HashMap<&'a str, S>) -> &mut Self {
         self.args
@@ -236,6 +823,107 @@ impl<'a> FormatBuilder<'a> {
         self
     }
 
+    /// Adds an argument formatted according to `locale`, which may differ from the locale of the
+    /// surrounding template, e.g. for technical codes which must keep English grouping.
+    pub fn arg_in_locale<T: LocaleFormat>(
+        &mut self,
+        key: &'a str,
+        value: &T,
+        locale: Locale,
+    ) -> &mut Self {
+        self.args.insert(key, value.format(locale));
+        self
+    }
+
+    /// Adds the count `n` as argument `key` formatted with [format_int] and selects the branches
+    /// of inline plural blocks for `key` according to the plural rules of `locale`.
+    ///
+    /// Plural blocks use the ICU MessageFormat syntax, e.g.
+    /// `{count, plural, one {# item} other {# items}}`. A branch is selected by an exact match
+    /// like `=0` first, then by the [PluralCategory] returned by [Locale::select_plural] and
+    /// finally by `other`. `#` is replaced with the formatted `n`.
+    pub fn plural(&mut self, key: &'a str, n: u64, locale: Locale) -> &mut Self {
+        if let Some(tpl) = plural::expand_plural_blocks(&self.tpl, key, n, locale) {
+            self.tpl = borrow::Cow::Owned(tpl);
+        }
+        self.args.insert(key, format_int(n, locale));
+        self
+    }
+
+    /// Sets whether argument values are wrapped in the Unicode directional isolates FSI (U+2068)
+    /// and PDI (U+2069) when formatting.
+    ///
+    /// Isolated values, e.g. user supplied names in an RTL script, do not affect the direction of
+    /// the surrounding text. Disabled by default.
+    pub fn isolate_args(&mut self, isolate: bool) -> &mut Self {
+        self.isolate_args = isolate;
+        self
+    }
+
+    /// Mirrors the parentheses and square brackets in the template if `locale` is written from
+    /// right to left, see [Locale::is_rtl]. Does nothing for other locales.
+    ///
+    /// The curly braces of placeholders are kept.
+    pub fn mirror_for(&mut self, locale: Locale) -> &mut Self {
+        if locale.is_rtl() {
+            self.tpl = borrow::Cow::Owned(mirror_brackets(&self.tpl));
+        }
+        self
+    }
+
+    /// Checks that `tpl` is a valid template, e.g. before formatting a template built at runtime.
+    ///
+    /// Placeholders like `{name}` must consist of letters, digits and underscores only. Plural
+    /// blocks like `{count, plural, one {# item} other {# items}}` (see
+    /// [plural](FormatBuilder::plural)) must have balanced braces.
+    pub fn validate_template(tpl: &str) -> Result<(), TemplateError> {
+        let bytes = tpl.as_bytes();
+        // The same characters as `\w` in the placeholder pattern of `SimpleCurlyFormat`.
+        let name_char = |i: usize| {
+            tpl[i..]
+                .chars()
+                .next()
+                .filter(|&c| c.is_alphanumeric() || c == '_')
+        };
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'}' => return Err(TemplateError::Unmatched(i)),
+                b'{' => {
+                    let start = i;
+                    i += 1;
+                    while let Some(c) = name_char(i) {
+                        i += c.len_utf8();
+                    }
+                    match bytes.get(i) {
+                        None => return Err(TemplateError::Unclosed(start)),
+                        Some(b'}') if i == start + 1 => {
+                            return Err(TemplateError::EmptyPlaceholder(start))
+                        }
+                        Some(b'}') => (),
+                        Some(b',') if i > start + 1 => {
+                            // Skip the branches of a plural block.
+                            let mut depth = 1;
+                            while depth > 0 {
+                                i += 1;
+                                match bytes.get(i) {
+                                    None => return Err(TemplateError::Unclosed(start)),
+                                    Some(b'{') => depth += 1,
+                                    Some(b'}') => depth -= 1,
+                                    Some(_) => (),
+                                }
+                            }
+                        }
+                        Some(_) => return Err(TemplateError::InvalidPlaceholder(start)),
+                    }
+                }
+                _ => (),
+            }
+            i += 1;
+        }
+        Ok(())
+    }
+
     /// Formats the given template with the added args with [try_format](FormatBuilder::try_format) if possible.
     /// If not, the template will be returned as is.
     pub fn format(&self) -> String {
@@ -245,29 +933,159 @@ impl<'a> FormatBuilder<'a> {
             .to_string()
     }
 
+    /// Writes the formatted template to `w` like [format](FormatBuilder::format) without
+    /// converting it to an owned `String` first.
+    ///
+    /// If formatting fails, the template is written as is.
+    pub fn write_to(&self, w: &mut impl io::Write) -> io::Result<()> {
+        let formatted = self.try_format().unwrap_or_else(|_| self.noop_format());
+        w.write_all(formatted.as_bytes())
+    }
+
     /// Formats the given template and returns an error if it failed.
-    pub fn try_format(&self) -> Result<borrow::Cow<'a, str>, DynFmtError> {
-        SimpleCurlyFormat.format(self.tpl, &self.args)
+    pub fn try_format(&self) -> Result<borrow::Cow<'a, str>, FormatError> {
+        if !self.isolate_args {
+            let args = TemplateArgs {
+                named: &self.args,
+                indexed: &self.indexed_args,
+            };
+            return self.format_tpl(SimpleCurlyFormat, args);
+        }
+
+        let isolate = |value: &String| format!("\u{2068}{}\u{2069}", value);
+        let named: HashMap<_, _> = self
+            .args
+            .iter()
+            .map(|(&key, value)| (key, isolate(value)))
+            .collect();
+        let indexed: HashMap<_, _> = self
+            .indexed_args
+            .iter()
+            .map(|(&index, value)| (index, isolate(value)))
+            .collect();
+        let args = TemplateArgs {
+            named: &named,
+            indexed: &indexed,
+        };
+        self.format_tpl(SimpleCurlyFormat, args)
     }
 
     /// Returns the template as is.
     fn noop_format(&self) -> borrow::Cow<'a, str> {
         // This should never fail to format, since NoopFormat is being used
-        dynfmt::NoopFormat.format(self.tpl, &self.args).unwrap()
+        self.format_tpl(dynfmt::NoopFormat, &self.args).unwrap()
+    }
+
+    /// Formats the template with `format`, borrowing from the original template if possible.
+    ///
+    /// Templates changed by [FormatBuilder::plural] or [FormatBuilder::mirror_for] are owned by
+    /// the builder, so their results are always owned.
+    fn format_tpl<F: for<'f> Format<'f>, A: FormatArgs>(
+        &self,
+        format: F,
+        args: A,
+    ) -> Result<borrow::Cow<'a, str>, FormatError> {
+        match &self.tpl {
+            borrow::Cow::Borrowed(tpl) => Ok(format.format(tpl, args)?),
+            borrow::Cow::Owned(tpl) => {
+                Ok(borrow::Cow::Owned(format.format(tpl, args)?.into_owned()))
+            }
+        }
+    }
+}
+
+/// Checks whether the ISO 15924 `script` is written from right to left.
+fn is_rtl_script(script: &str) -> bool {
+    matches!(script, "Arab" | "Hebr" | "Syrc" | "Thaa")
+}
+
+/// Swaps opening and closing parentheses and square brackets in `s`.
+fn mirror_brackets(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '(' => ')',
+            ')' => '(',
+            '[' => ']',
+            ']' => '[',
+            c => c,
+        })
+        .collect()
+}
+
+/// The named and positional arguments of a [FormatBuilder] passed to `dynfmt`.
+struct TemplateArgs<'b, 'a> {
+    named: &'b HashMap<&'a str, String>,
+    indexed: &'b HashMap<usize, String>,
+}
+
+impl FormatArgs for TemplateArgs<'_, '_> {
+    fn get_index(&self, index: usize) -> Result<Option<Argument<'_>>, ()> {
+        Ok(self.indexed.get(&index).map(|arg| arg as Argument<'_>))
+    }
+
+    fn get_key(&self, key: &str) -> Result<Option<Argument<'_>>, ()> {
+        Ok(self.named.get(key).map(|arg| arg as Argument<'_>))
+    }
+}
+
+/// Describes why [FormatBuilder::try_format] failed.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum FormatError {
+    /// The template contains a placeholder without argument, e.g. `{name}` or `{0}`.
+    #[error("Missing argument `{0}`.")]
+    MissingKey(String),
+    /// The template could not be parsed.
+    #[error("Malformed template: {0}")]
+    MalformedTemplate(String),
+    /// An argument could not be formatted or written.
+    #[error("Formatting failed: {0}")]
+    Other(String),
+}
+
+impl From<DynFmtError<'_>> for FormatError {
+    fn from(err: DynFmtError<'_>) -> Self {
+        match err {
+            DynFmtError::MissingArg(position) => FormatError::MissingKey(position.to_string()),
+            DynFmtError::BadFormat(_) | DynFmtError::Parse(_) => {
+                FormatError::MalformedTemplate(err.to_string())
+            }
+            err => FormatError::Other(err.to_string()),
+        }
     }
 }
 
+/// Describes why a template is invalid, see [FormatBuilder::validate_template].
+///
+/// Each variant contains the byte offset of the offending brace.
+#[derive(Clone, Copy, Debug, Error, Eq, PartialEq)]
+pub enum TemplateError {
+    /// A `{` is never closed.
+    #[error("Unclosed `{{` at byte {0}.")]
+    Unclosed(usize),
+    /// A `}` was not opened.
+    #[error("Unmatched `}}` at byte {0}.")]
+    Unmatched(usize),
+    /// A placeholder has no name, i.e. `{}`.
+    #[error("Empty placeholder at byte {0}.")]
+    EmptyPlaceholder(usize),
+    /// A placeholder contains characters other than letters, digits and underscores.
+    #[error("Invalid placeholder at byte {0}.")]
+    InvalidPlaceholder(usize),
+}
+
 /// A trait to help with creating a [FormatBuilder](FormatBuilder).
 pub trait ToFormat {
     /// Create a `FormatBuilder` from `&self`.
-    fn to_format(&self) -> FormatBuilder;
+    fn to_format(&self) -> FormatBuilder<'_>;
 }
 
-impl<'a> ToFormat for &'a str {
-    fn to_format(&self) -> FormatBuilder {
+impl ToFormat for &str {
+    fn to_format(&self) -> FormatBuilder<'_> {
         FormatBuilder {
-            tpl: self,
+            tpl: borrow::Cow::Borrowed(self),
             args: HashMap::new(),
+            indexed_args: HashMap::new(),
+            isolate_args: false,
         }
     }
 }
@@ -277,37 +1095,500 @@ pub fn format_int<N: num_format::ToFormattedStr>(n: N, locale: Locale) -> String
     n.to_formatted_string::<num_format::Locale>(&locale.into())
 }
 
+/// Formats `n` like [format_int] but avoids allocating where possible.
+///
+/// The single digits `0` to `9` are borrowed from a static string, as they look the same in all
+/// locales. All other values are allocated, numbers below 1000 without going through
+/// [num_format] as they need no grouping.
+pub fn format_int_cow<N: num_format::ToFormattedStr>(
+    n: N,
+    locale: Locale,
+) -> borrow::Cow<'static, str> {
+    const DIGITS: &str = "0123456789";
+
+    let plain = plain_digits(&n);
+    match split_minus(plain.as_str()) {
+        (false, digits) if digits.len() == 1 => {
+            let digit = usize::from(digits.as_bytes()[0] - b'0');
+            borrow::Cow::Borrowed(&DIGITS[digit..digit + 1])
+        }
+        (true, digits) if digits.len() <= 3 => {
+            let minus = locale.num_format_locale().minus_sign();
+            borrow::Cow::Owned(format!("{}{}", minus, digits))
+        }
+        (false, digits) if digits.len() <= 3 => borrow::Cow::Owned(digits.to_owned()),
+        _ => borrow::Cow::Owned(format_int(n, locale)),
+    }
+}
+
+/// Writes `n` without grouping separators and with an ASCII minus sign to a stack buffer, to
+/// access its digits independent of the integer type.
+fn plain_digits<N: num_format::ToFormattedStr>(n: &N) -> num_format::Buffer {
+    let format = num_format::CustomFormat::builder()
+        .grouping(num_format::Grouping::Posix)
+        .minus_sign("-")
+        .build()
+        .expect("the plain number format is valid");
+    let mut buffer = num_format::Buffer::new();
+    buffer.write_formatted(n, &format);
+    buffer
+}
+
+/// Splits a number written by [plain_digits] into whether it is negative and its digits.
+fn split_minus(plain: &str) -> (bool, &str) {
+    match plain.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, plain),
+    }
+}
+
+/// Formats `n` like [format_int] with at least `min_digits` digits by padding with zeros.
+///
+/// The zeros are grouped like all other digits and a minus sign precedes them, e.g.
+/// `format_int_padded(-42, 5, Locale::de_DE)` is `-00.042`.
+pub fn format_int_padded<N: num_format::ToFormattedStr>(
+    n: N,
+    min_digits: usize,
+    locale: Locale,
+) -> String {
+    let nf_locale: num_format::Locale = locale.into();
+    let plain = plain_digits(&n);
+    let (negative, digits) = split_minus(plain.as_str());
+    let digits = format!("{:0>width$}", digits, width = min_digits);
+
+    let mut formatted = String::new();
+    if negative {
+        formatted.push_str(nf_locale.minus_sign());
+    }
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            formatted.push_str(nf_locale.separator());
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// Splits `n` formatted like [format_int] into its sign, digit groups and grouping separator, e.g.
+/// to render the separators with their own styling.
+///
+/// `-1234567` in German has the sign `"-"`, the groups `["1", "234", "567"]` and the separator
+/// `"."`. Displaying the parts yields the same string as [format_int].
+pub fn format_int_parts<N: num_format::ToFormattedStr>(n: N, locale: Locale) -> NumberParts {
+    let nf_locale = locale.num_format_locale();
+    let plain = plain_digits(&n);
+    let (negative, digits) = split_minus(plain.as_str());
+
+    // The first group takes the digits which do not fill a complete group.
+    let first = match digits.len() % 3 {
+        0 => 3,
+        len => len,
+    };
+    let mut groups = vec![digits[..first].to_owned()];
+    groups.extend(
+        digits.as_bytes()[first..]
+            .chunks(3)
+            .map(|group| String::from_utf8_lossy(group).into_owned()),
+    );
+
+    NumberParts {
+        sign: if negative { nf_locale.minus_sign() } else { "" },
+        groups,
+        separator: nf_locale.separator(),
+    }
+}
+
+/// The parts of a formatted integer returned by [format_int_parts].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NumberParts {
+    /// The minus sign of negative numbers, empty otherwise.
+    pub sign: &'static str,
+    /// The groups of digits from the most significant one.
+    pub groups: Vec<String>,
+    /// The separator put between groups.
+    pub separator: &'static str,
+}
+
+impl std::fmt::Display for NumberParts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.sign, self.groups.join(self.separator))
+    }
+}
+
+/// Formats `n` without grouping separators but with the minus sign of `locale`, e.g. for account
+/// or reference numbers like `-20240815`.
+pub fn format_reference<N: num_format::ToFormattedStr>(n: N, locale: Locale) -> String {
+    let plain = plain_digits(&n);
+    match split_minus(plain.as_str()) {
+        (true, digits) => format!("{}{}", locale.num_format_locale().minus_sign(), digits),
+        (false, digits) => digits.to_owned(),
+    }
+}
+
 /// Formats `f` as an `f64` with `precision` digits after the decimal point according to `locale`.
 ///
 /// If necessary `f` is rounded to `precision` by rounding halves away from zero.
 pub fn format_f64<N: Into<f64>>(f: N, precision: u8, locale: Locale) -> String {
     let nf_locale: num_format::Locale = locale.into();
-    format_num!(&format!(",.{}f", precision), f)
-        .replace(',', "!")
-        .replace('.', nf_locale.decimal())
-        .replace('!', nf_locale.separator())
+    format_f64_with_separators(
+        f.into(),
+        precision,
+        nf_locale.decimal(),
+        nf_locale.separator(),
+    )
 }
 
-#[cfg(test)]
-mod tests {
-    use super::{format_f64, Locale};
+/// Formats `f` like [format_f64] but with the given separators instead of the ones of a locale,
+/// e.g. `'` for Swiss style grouping like `1'234.56`.
+pub fn format_f64_custom<N: Into<f64>>(
+    f: N,
+    precision: u8,
+    decimal: char,
+    grouping: char,
+) -> String {
+    format_f64_with_separators(
+        f.into(),
+        precision,
+        decimal.encode_utf8(&mut [0; 4]),
+        grouping.encode_utf8(&mut [0; 4]),
+    )
+}
 
-    #[test]
-    fn format() {
-        assert_eq!(&format_f64(0, 0, Locale::de_DE), "0");
-        for precision in 1..10 {
-            assert_eq!(
-                format_f64(0, precision, Locale::de_DE),
-                "0".to_string() + "," + &"0".repeat(precision as usize)
-            );
+/// Formats `f` with `precision` digits after the `decimal` separator and `grouping` separators.
+fn format_f64_with_separators(f: f64, precision: u8, decimal: &str, grouping: &str) -> String {
+    let mut formatted = String::new();
+    for c in format_num!(&format!(",.{}f", precision), f).chars() {
+        match c {
+            ',' => formatted.push_str(grouping),
+            '.' => formatted.push_str(decimal),
+            c => formatted.push(c),
         }
+    }
+    formatted
+}
 
-        assert_eq!(&format_f64(0.0, 0, Locale::de_DE), "0");
-        assert_eq!(&format_f64(0.0000000000000001, 0, Locale::de_DE), "0");
-        assert_eq!(
-            &format_f64(0.0000000000000001, 16, Locale::de_DE),
-            "0,0000000000000001"
-        );
+/// Formats `f` like [format_f64] and reports whether rounding to `precision` changed the value.
+///
+/// E.g. `1.005` formatted with precision 2 reports `true`, while `1.5` does not.
+pub fn try_format_f64<N: Into<f64>>(f: N, precision: u8, locale: Locale) -> (String, bool) {
+    let f = f.into();
+    let formatted = format_f64(f, precision, locale);
+    let rounded = parse_f64(&formatted, locale) != Ok(f);
+    (formatted, rounded)
+}
+
+/// Formats `f` like [format_f64] but without grouping separators, e.g. `1234567,89` in German.
+///
+/// Grouping separators often break parsing when numbers are copied into spreadsheets or input
+/// fields, while the localized decimal separator is expected there. The tradeoff is that large
+/// numbers are harder to read, so use this only for output which is likely to be copied.
+pub fn format_f64_copyable<N: Into<f64>>(f: N, precision: u8, locale: Locale) -> String {
+    format_num!(&format!(".{}f", precision), f).replace('.', locale.num_format_locale().decimal())
+}
+
+/// A value that can be formatted according to a [Locale].
+pub trait LocaleFormat {
+    /// Formats `self` according to `locale`.
+    fn format(&self, locale: Locale) -> String;
+}
+
+macro_rules! impl_locale_format_int {
+    ($($t:ty),*) => {
+        $(
+            impl LocaleFormat for $t {
+                fn format(&self, locale: Locale) -> String {
+                    format_int(*self, locale)
+                }
+            }
+        )*
+    };
+}
+
+impl_locale_format_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Formats `opt` according to `locale` or returns the locale's placeholder for missing data if
+/// `opt` is `None`.
+///
+/// See [Locale::not_available] for the placeholders.
+pub fn format_option<T: LocaleFormat>(opt: Option<T>, locale: Locale) -> String {
+    match opt {
+        Some(value) => value.format(locale),
+        None => locale.not_available().to_owned(),
+    }
+}
+
+/// An object safe interface to the formatting functions of a [Locale].
+///
+/// This allows to format numbers behind a `dyn LocaleFormatter` without knowing the locale at
+/// compile time. Only [LocaleFormatter::locale] has to be implemented.
+pub trait LocaleFormatter {
+    /// Returns the locale used for formatting.
+    fn locale(&self) -> Locale;
+
+    /// Formats `n` like [format_int].
+    fn format_int(&self, n: i64) -> String {
+        format_int(n, self.locale())
+    }
+
+    /// Formats `f` like [format_f64].
+    fn format_f64(&self, f: f64, precision: u8) -> String {
+        format_f64(f, precision, self.locale())
+    }
+
+    /// Formats `f` like [format_rounded].
+    fn format_rounded(&self, f: f64, precision: i8) -> String {
+        format_rounded(f, precision, self.locale())
+    }
+
+    /// Formats `f` like [format_scientific].
+    fn format_scientific(&self, f: f64, precision: u8, style: ExponentStyle) -> String {
+        format_scientific(f, precision, style, self.locale())
+    }
+}
+
+impl LocaleFormatter for Locale {
+    fn locale(&self) -> Locale {
+        *self
+    }
+}
+
+/// Formats a progress like "3 of 10" with both numbers formatted with [format_int], e.g.
+/// "3 von 10" in German.
+pub fn format_of(current: u64, total: u64, locale: Locale) -> String {
+    format!(
+        "{} {} {}",
+        format_int(current, locale),
+        locale.data().of,
+        format_int(total, locale)
+    )
+}
+
+/// Parses `s` as an integer formatted according to `locale`.
+///
+/// See [parse_f64] for the accepted formats.
+pub fn parse_int(s: &str, locale: Locale) -> Result<i64, ParseNumberError> {
+    let (int, fraction) = normalize_number(s, locale)?;
+    if fraction.is_some() {
+        return Err(ParseNumberError(s.to_owned()));
+    }
+    int.parse().map_err(|_| ParseNumberError(s.to_owned()))
+}
+
+/// Parses `s` as a floating point number formatted according to `locale`.
+///
+/// Grouping separators are optional, e.g. both `1.234,56` and `1234,56` are accepted for
+/// [Locale::de_DE]. Since grouping and decimal separators could be confused, the following
+/// rules apply:
+///
+/// - Only the locale's decimal separator is accepted as decimal separator and it may appear at
+///   most once.
+/// - If the integer part contains grouping separators, the first group has one to three digits and
+///   all following groups have exactly three digits.
+/// - For locales grouping with a (no-break) space, a plain ASCII space is accepted as well.
+///
+/// Thus `1234.56` is rejected for [Locale::de_DE], as `.` is the grouping separator and `56` is no
+/// valid group of digits.
+pub fn parse_f64(s: &str, locale: Locale) -> Result<f64, ParseNumberError> {
+    let (int, fraction) = normalize_number(s, locale)?;
+    let normalized = match fraction {
+        Some(fraction) => format!("{}.{}", int, fraction),
+        None => int,
+    };
+    normalized
+        .parse()
+        .map_err(|_| ParseNumberError(s.to_owned()))
+}
+
+/// Splits the localized number `s` into its integer part (including the sign) and its fraction
+/// digits, removing all grouping separators.
+fn normalize_number(s: &str, locale: Locale) -> Result<(String, Option<&str>), ParseNumberError> {
+    let err = || ParseNumberError(s.to_owned());
+    let nf_locale: num_format::Locale = locale.into();
+    let is_digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+
+    let trimmed = s.trim();
+    let (sign, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+
+    let (int, fraction) = match unsigned.split_once(nf_locale.decimal()) {
+        Some((int, fraction)) if is_digits(fraction) => (int, Some(fraction)),
+        Some(_) => return Err(err()),
+        None => (unsigned, None),
+    };
+
+    let separator = nf_locale.separator();
+    let groups: Vec<&str> = if separator.trim().is_empty() {
+        int.split(|c: char| separator.contains(c) || c == ' ')
+            .collect()
+    } else {
+        int.split(separator).collect()
+    };
+    let valid = match groups.split_first() {
+        Some((first, [])) => is_digits(first),
+        Some((first, rest)) => {
+            is_digits(first)
+                && first.len() <= 3
+                && rest
+                    .iter()
+                    .all(|group| is_digits(group) && group.len() == 3)
+        }
+        None => false,
+    };
+    if !valid {
+        return Err(err());
+    }
+
+    Ok((format!("{}{}", sign, groups.concat()), fraction))
+}
+
+/// Checks whether `s` is a number formatted according to `locale`.
+///
+/// Returns a [FormatHint] describing the problem otherwise, e.g. if `s` uses the separators of
+/// another locale like `1,234.56` for [Locale::de_DE].
+pub fn validate_number_format(s: &str, locale: Locale) -> Result<(), FormatHint> {
+    if parse_f64(s, locale).is_ok() {
+        return Ok(());
+    }
+
+    let nf_locale: num_format::Locale = locale.into();
+    let other_separators = ALL_LOCALES.iter().any(|&other| {
+        let nf_other: num_format::Locale = other.into();
+        nf_other.decimal() != nf_locale.decimal() && parse_f64(s, other).is_ok()
+    });
+    if other_separators {
+        Err(FormatHint::WrongSeparators {
+            decimal: nf_locale.decimal(),
+            grouping: nf_locale.separator(),
+        })
+    } else {
+        Err(FormatHint::Invalid)
+    }
+}
+
+/// Guesses which of the `candidates` the number `s` is formatted for, e.g. for imported data.
+///
+/// Returns the first candidate [parse_f64] accepts `s` for, as long as all accepted candidates
+/// parse it to the same value. Returns `None` if no candidate accepts `s` or if it is ambiguous:
+/// `1.234,56` is clearly German, but `1.234` is 1234 in German and 1.234 in English.
+pub fn guess_number_locale(s: &str, candidates: &[Locale]) -> Option<Locale> {
+    let mut matches = candidates
+        .iter()
+        .filter_map(|&locale| Some((locale, parse_f64(s, locale).ok()?)));
+    let (guess, value) = matches.next()?;
+    matches.all(|(_, other)| other == value).then_some(guess)
+}
+
+/// Describes why a string is no number formatted according to a locale.
+#[derive(Debug, Error, Clone, Copy, Eq, PartialEq)]
+pub enum FormatHint {
+    /// The number uses the separators of another locale.
+    #[error("Use {decimal:?} as decimal separator and {grouping:?} as grouping separator")]
+    WrongSeparators {
+        /// The decimal separator of the expected locale.
+        decimal: &'static str,
+        /// The grouping separator of the expected locale.
+        grouping: &'static str,
+    },
+    /// The input is no number in any supported locale.
+    #[error("Not a number")]
+    Invalid,
+}
+
+/// Received a string which is not a number formatted according to the expected locale.
+#[derive(Debug, Error, Clone, Eq, PartialEq)]
+#[error("Invalid number {0}")]
+pub struct ParseNumberError(pub String);
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        borrow, format_f64, format_f64_copyable, format_f64_custom, format_int, format_int_cow,
+        format_int_padded, format_int_parts, format_of, format_option, format_reference,
+        guess_number_locale, is_rtl_script, mirror_brackets, parse_f64, parse_int, try_format_f64,
+        validate_number_format, CatalogLike, ConflictPolicy, ExponentStyle, FallbackFromEnvError,
+        FormatBuilder, FormatError, FormatHint, IncompletePlural, Locale, LocaleFormatter,
+        Localizer, NumberParts, PluralFormWarning, TemplateError, ToFormat, ALL_LOCALES,
+    };
+    use crate::test_support::{catalog, plural_catalog};
+    use gettext::Catalog;
+    use std::collections::HashMap;
+
+    #[test]
+    fn plural_forms() {
+        let header = |n: usize| {
+            format!(
+                "Content-Type: text/plain; charset=UTF-8\nPlural-Forms: nplurals={}; plural=(n != 1);\n",
+                n
+            )
+        };
+        let catalogs = HashMap::from([
+            (Locale::de_DE, catalog(&[("", &header(3))])),
+            (Locale::en_GB, catalog(&[("", &header(2))])),
+            (Locale::fr_FR, Catalog::empty()),
+        ]);
+        let localizer = Localizer::new(catalogs, Locale::en_GB).unwrap();
+
+        assert_eq!(
+            localizer.check_plural_forms(),
+            vec![PluralFormWarning {
+                locale: Locale::de_DE,
+                expected: 2,
+                declared: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn plural_completeness() {
+        // A simplified Russian rule which is sufficient for the numbers below 20.
+        let catalogs = HashMap::from([(
+            Locale::ru_RU,
+            plural_catalog(
+                "nplurals=3; plural=(n==1 ? 0 : n<5 ? 1 : 2);",
+                &[
+                    ("{} file", &["{} файл", "{} файла"]),
+                    ("{} day", &["{} день", "{} дня", "{} дней"]),
+                ],
+            ),
+        )]);
+        let localizer = Localizer::new(catalogs, Locale::ru_RU).unwrap();
+        let msgids = [
+            ("{} file", "{} files"),
+            ("{} day", "{} days"),
+            ("{} hour", "{} hours"),
+        ];
+
+        assert_eq!(
+            localizer.check_plural_completeness(Locale::ru_RU, &msgids),
+            vec![IncompletePlural {
+                msgid: "{} file".to_owned(),
+                expected: 3,
+                translated: 2,
+            }]
+        );
+        assert!(localizer
+            .check_plural_completeness(Locale::de_DE, &msgids)
+            .is_empty());
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(&format_f64(0, 0, Locale::de_DE), "0");
+        for precision in 1..10 {
+            assert_eq!(
+                format_f64(0, precision, Locale::de_DE),
+                "0".to_string() + "," + &"0".repeat(precision as usize)
+            );
+        }
+
+        assert_eq!(&format_f64(0.0, 0, Locale::de_DE), "0");
+        assert_eq!(&format_f64(0.0000000000000001, 0, Locale::de_DE), "0");
+        assert_eq!(
+            &format_f64(0.0000000000000001, 16, Locale::de_DE),
+            "0,0000000000000001"
+        );
         assert_eq!(&format_f64(0.001, 2, Locale::de_DE), "0,00");
         assert_eq!(&format_f64(0.005, 2, Locale::de_DE), "0,01");
         assert_eq!(&format_f64(0.009, 2, Locale::de_DE), "0,01");
@@ -331,4 +1612,713 @@ mod tests {
         assert_eq!(&format_f64(1234, 5, Locale::de_DE), "1.234,00000");
         assert_eq!(&format_f64(-1234, 5, Locale::de_DE), "-1.234,00000");
     }
+
+    #[test]
+    fn option() {
+        assert_eq!(format_option(Some(1234), Locale::de_DE), "1.234");
+        assert_eq!(format_option(Some(1234), Locale::en_GB), "1,234");
+        assert_eq!(format_option(None::<u32>, Locale::de_DE), "k. A.");
+        assert_eq!(format_option(None::<u32>, Locale::en_GB), "N/A");
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_f64("1234,56", Locale::de_DE), Ok(1234.56));
+        assert_eq!(parse_f64("1.234,56", Locale::de_DE), Ok(1234.56));
+        assert_eq!(parse_f64("-1.234.567,5", Locale::de_DE), Ok(-1234567.5));
+        assert_eq!(parse_f64("1,234.56", Locale::en_GB), Ok(1234.56));
+        assert_eq!(parse_f64("1 234,56", Locale::fr_FR), Ok(1234.56));
+        assert_eq!(parse_f64("1\u{202f}234,56", Locale::fr_FR), Ok(1234.56));
+        assert!(parse_f64("1234.56", Locale::de_DE).is_err());
+        assert!(parse_f64("1.23,4", Locale::de_DE).is_err());
+        assert!(parse_f64("1,5,6", Locale::de_DE).is_err());
+        assert!(parse_f64(",5", Locale::de_DE).is_err());
+
+        assert_eq!(parse_int("1.234", Locale::de_DE), Ok(1234));
+        assert_eq!(parse_int("+1234", Locale::de_DE), Ok(1234));
+        assert_eq!(parse_int("-1,234", Locale::en_GB), Ok(-1234));
+        assert!(parse_int("1234,5", Locale::de_DE).is_err());
+        assert!(parse_int("", Locale::de_DE).is_err());
+    }
+
+    #[test]
+    fn canonical_code() {
+        let locales = [
+            (Locale::de_DE, "de_DE"),
+            (Locale::en_GB, "en_GB"),
+            (Locale::es_ES, "es_ES"),
+            (Locale::fr_FR, "fr_FR"),
+            (Locale::it_IT, "it_IT"),
+            (Locale::pt_PT, "pt_PT"),
+            (Locale::ru_RU, "ru_RU"),
+        ];
+        for (locale, code) in locales {
+            assert_eq!(locale.html_lang(), code.replace('_', "-"));
+            assert_eq!(locale.as_ref(), code);
+            assert_eq!(locale.stable_key(), locale.as_str());
+            assert_eq!(locale.stable_key(), code);
+            assert_eq!(locale.to_posix(), format!("{}.UTF-8", code));
+            assert_eq!(<&'static str>::from(locale), code);
+            assert_eq!(code.parse::<Locale>().unwrap(), locale);
+            assert_eq!(locale.html_lang().parse::<Locale>().unwrap(), locale);
+            assert_eq!(code.to_lowercase().parse::<Locale>().unwrap(), locale);
+            assert_eq!(code[..2].to_uppercase().parse::<Locale>().unwrap(), locale);
+        }
+
+        for locale in ALL_LOCALES {
+            let base = locale.to_language_base();
+            assert_eq!(base.as_str()[..2], locale.as_str()[..2]);
+            assert_eq!(base.to_language_base(), base);
+        }
+
+        assert_eq!(Locale::de_DE.to_posix(), "de_DE.UTF-8");
+        for locale in ALL_LOCALES {
+            let expected = if locale == Locale::ru_RU {
+                "Cyrl"
+            } else {
+                "Latn"
+            };
+            assert_eq!(locale.script(), expected);
+        }
+        assert_eq!(Locale::de_DE.num_format_locale(), num_format::Locale::de);
+        assert_eq!(Locale::en_GB.num_format_locale(), num_format::Locale::en_GB);
+        let nf_locale: num_format::Locale = (&Locale::de_DE).into();
+        assert_eq!(nf_locale, num_format::Locale::de);
+        #[cfg(feature = "chrono")]
+        {
+            let chrono_locale: chrono::Locale = (&Locale::fr_FR).into();
+            assert_eq!(chrono_locale, chrono::Locale::fr_FR);
+        }
+        #[cfg(feature = "unic-langid")]
+        {
+            use unic_langid::LanguageIdentifier;
+
+            for locale in ALL_LOCALES {
+                let langid = LanguageIdentifier::from(locale);
+                assert_eq!(langid.to_string(), locale.html_lang());
+                assert_eq!(Locale::try_from(langid).unwrap(), locale);
+            }
+            let langid: LanguageIdentifier = "de-AT".parse().unwrap();
+            assert!(Locale::try_from(langid).is_err());
+        }
+    }
+
+    #[test]
+    fn arg_in_locale() {
+        let formatted = "{amount} Stück, Code {code}"
+            .to_format()
+            .arg_in_locale("amount", &1234, Locale::de_DE)
+            .arg_in_locale("code", &56789, Locale::en_GB)
+            .format();
+        assert_eq!(formatted, "1.234 Stück, Code 56,789");
+    }
+
+    #[test]
+    fn mock_catalog() {
+        /// Returns the msgid in uppercase to make translated strings recognizable.
+        struct Uppercase(HashMap<String, String>);
+
+        impl CatalogLike for Uppercase {
+            fn gettext<'a>(&'a self, msg_id: &'a str) -> &'a str {
+                self.0.get(msg_id).map_or(msg_id, String::as_str)
+            }
+
+            fn ngettext<'a>(&'a self, msg_id: &'a str, msg_id_plural: &'a str, n: u64) -> &'a str {
+                self.gettext(if n == 1 { msg_id } else { msg_id_plural })
+            }
+
+            fn pgettext<'a>(&'a self, _: &'a str, msg_id: &'a str) -> &'a str {
+                self.gettext(msg_id)
+            }
+
+            fn npgettext<'a>(
+                &'a self,
+                _: &'a str,
+                msg_id: &'a str,
+                msg_id_plural: &'a str,
+                n: u64,
+            ) -> &'a str {
+                self.ngettext(msg_id, msg_id_plural, n)
+            }
+        }
+
+        let uppercase = |msgids: &[&str]| {
+            Uppercase(
+                msgids
+                    .iter()
+                    .map(|msgid| (msgid.to_string(), msgid.to_uppercase()))
+                    .collect(),
+            )
+        };
+        let localizer = Localizer::new(
+            HashMap::from([(Locale::en_GB, uppercase(&["Yes", "files"]))]),
+            Locale::en_GB,
+        )
+        .unwrap();
+
+        let catalog = localizer.get_catalog(Locale::de_DE);
+        assert_eq!(catalog.gettext("Yes"), "YES");
+        assert_eq!(catalog.gettext("No"), "No");
+        let translator = localizer.translator(Locale::en_GB);
+        assert_eq!(translator.ntr("file", "files", 2), "FILES");
+        assert_eq!(translator.ntr("file", "files", 1), "file");
+    }
+
+    #[test]
+    fn arg_string() {
+        let name = String::from("Anna");
+        let formatted = "{greeting}, {name}!"
+            .to_format()
+            .arg("greeting", &"Hallo")
+            .arg_string("name", name)
+            .format();
+        assert_eq!(formatted, "Hallo, Anna!");
+    }
+
+    #[test]
+    fn fallbacks() {
+        let catalogs = || {
+            HashMap::from([
+                (Locale::de_DE, catalog(&[("Yes", "Ja")])),
+                (Locale::fr_FR, catalog(&[("Yes", "Oui")])),
+            ])
+        };
+
+        let localizer = Localizer::new_with_fallbacks(
+            catalogs(),
+            vec![Locale::en_GB, Locale::fr_FR, Locale::de_DE],
+        )
+        .unwrap();
+        assert_eq!(localizer.fallback(), Locale::fr_FR);
+        assert_eq!(localizer.get_catalog(Locale::it_IT).gettext("Yes"), "Oui");
+        assert_eq!(localizer.get_catalog(Locale::de_DE).gettext("Yes"), "Ja");
+
+        // A merged catalog for an earlier fallback takes precedence.
+        let mut localizer = localizer;
+        let english = HashMap::from([(Locale::en_GB, catalog(&[("Yes", "Yes!")]))]);
+        localizer
+            .merge(
+                Localizer::new(english, Locale::en_GB).unwrap(),
+                ConflictPolicy::Error,
+            )
+            .unwrap();
+        assert_eq!(localizer.fallback(), Locale::en_GB);
+        assert_eq!(localizer.resolve(Locale::it_IT), Locale::en_GB);
+        assert_eq!(localizer.get_catalog(Locale::it_IT).gettext("Yes"), "Yes!");
+
+        let err = Localizer::new_with_fallbacks(catalogs(), vec![Locale::en_GB, Locale::it_IT]);
+        assert_eq!(err.err().unwrap().0, vec![Locale::en_GB, Locale::it_IT]);
+        assert!(Localizer::new_with_fallbacks(catalogs(), vec![]).is_err());
+    }
+
+    #[test]
+    fn validate_number() {
+        assert_eq!(validate_number_format("1.234,56", Locale::de_DE), Ok(()));
+        assert_eq!(validate_number_format("1234,56", Locale::de_DE), Ok(()));
+        let hint = validate_number_format("1,234.56", Locale::de_DE).unwrap_err();
+        assert_eq!(
+            hint,
+            FormatHint::WrongSeparators {
+                decimal: ",",
+                grouping: ".",
+            }
+        );
+        assert_eq!(
+            hint.to_string(),
+            r#"Use "," as decimal separator and "." as grouping separator"#
+        );
+        assert_eq!(
+            validate_number_format("12.5", Locale::fr_FR),
+            Err(FormatHint::WrongSeparators {
+                decimal: ",",
+                grouping: "\u{202f}",
+            })
+        );
+        assert_eq!(
+            validate_number_format("12a", Locale::de_DE),
+            Err(FormatHint::Invalid)
+        );
+    }
+
+    #[test]
+    fn resolve() {
+        let catalogs = HashMap::from([
+            (Locale::de_DE, catalog(&[("Yes", "Ja")])),
+            (Locale::en_GB, catalog(&[])),
+        ]);
+        let localizer = Localizer::new(catalogs, Locale::en_GB).unwrap();
+
+        assert_eq!(localizer.resolve(Locale::de_DE), Locale::de_DE);
+        assert_eq!(localizer.resolve(Locale::fr_FR), Locale::en_GB);
+        assert_eq!(localizer.get_catalog(Locale::fr_FR).gettext("Yes"), "Yes");
+    }
+
+    #[test]
+    fn dyn_formatter() {
+        let german = Locale::de_DE.as_formatter();
+        let english = Locale::en_GB.as_formatter();
+        let formatters: [&dyn LocaleFormatter; 2] = [&german, &english];
+
+        let formatted: Vec<_> = formatters
+            .iter()
+            .map(|formatter| {
+                format!(
+                    "{} {} {} {}",
+                    formatter.format_int(-1234),
+                    formatter.format_f64(1234.5, 1),
+                    formatter.format_rounded(1250.0, -2),
+                    formatter.format_scientific(1234.0, 1, ExponentStyle::E),
+                )
+            })
+            .collect();
+        assert_eq!(
+            formatted,
+            ["-1.234 1.234,5 1.300 1,2e3", "-1,234 1,234.5 1,300 1.2e3"]
+        );
+    }
+
+    #[test]
+    fn from_environment() {
+        let env = |vars: &[(&str, &str)]| {
+            let vars: HashMap<_, _> = vars
+                .iter()
+                .map(|&(name, value)| (name.to_owned(), value.to_owned()))
+                .collect();
+            Locale::from_env_vars(|name| vars.get(name).cloned())
+        };
+
+        assert_eq!(env(&[("LANGUAGE", "fr:de")]), Some(Locale::fr_FR));
+        assert_eq!(
+            env(&[("LANGUAGE", "sv:de_AT"), ("LANG", "fr_FR.UTF-8")]),
+            Some(Locale::de_DE)
+        );
+        assert_eq!(
+            env(&[("LANGUAGE", "sv"), ("LANG", "fr_FR.UTF-8")]),
+            Some(Locale::fr_FR)
+        );
+        assert_eq!(
+            env(&[
+                ("LC_ALL", ""),
+                ("LC_MESSAGES", "it_IT@euro"),
+                ("LANG", "de_DE")
+            ]),
+            Some(Locale::it_IT)
+        );
+        assert_eq!(env(&[("LANG", "C")]), None);
+        assert_eq!(env(&[]), None);
+    }
+
+    #[test]
+    fn precision_loss() {
+        let (formatted, rounded) = try_format_f64(1.005, 2, Locale::de_DE);
+        assert!(rounded);
+        assert!(formatted == "1,00" || formatted == "1,01", "{}", formatted);
+        assert_eq!(
+            try_format_f64(1234.5, 2, Locale::de_DE),
+            ("1.234,50".to_owned(), false)
+        );
+        assert_eq!(
+            try_format_f64(1234.4, 0, Locale::en_GB),
+            ("1,234".to_owned(), true)
+        );
+        assert_eq!(
+            try_format_f64(-0.001, 2, Locale::fr_FR),
+            ("0,00".to_owned(), true)
+        );
+        assert_eq!(
+            try_format_f64(0, 2, Locale::fr_FR),
+            ("0,00".to_owned(), false)
+        );
+    }
+
+    #[test]
+    fn padded() {
+        assert_eq!(format_int_padded(42, 5, Locale::de_DE), "00.042");
+        assert_eq!(format_int_padded(7, 3, Locale::en_GB), "007");
+        assert_eq!(format_int_padded(-42, 3, Locale::en_GB), "-042");
+        assert_eq!(format_int_padded(1234567, 3, Locale::de_DE), "1.234.567");
+        assert_eq!(
+            format_int_padded(1234567, 3, Locale::de_DE),
+            format_int(1234567, Locale::de_DE)
+        );
+        assert_eq!(format_int_padded(0, 0, Locale::de_DE), "0");
+        assert_eq!(format_int_padded(42usize, 4, Locale::de_DE), "0.042");
+        assert_eq!(format_int_padded(-42isize, 4, Locale::en_GB), "-0,042");
+    }
+
+    #[test]
+    fn copyable() {
+        assert_eq!(
+            format_f64_copyable(1234567.891, 2, Locale::de_DE),
+            "1234567,89"
+        );
+        assert_eq!(
+            format_f64_copyable(1234567.891, 2, Locale::en_GB),
+            "1234567.89"
+        );
+        assert_eq!(format_f64_copyable(-1234.5, 1, Locale::fr_FR), "-1234,5");
+        assert_eq!(format_f64_copyable(1234, 0, Locale::ru_RU), "1234");
+    }
+
+    #[test]
+    fn isolate_args() {
+        let formatted = "{name} hat {count} Nachrichten"
+            .to_format()
+            .arg("name", &"עמית")
+            .arg("count", &3)
+            .isolate_args(true)
+            .format();
+        assert_eq!(
+            formatted,
+            "\u{2068}עמית\u{2069} hat \u{2068}3\u{2069} Nachrichten"
+        );
+
+        let formatted = "Hallo {name}".to_format().arg("name", &"Anna").format();
+        assert_eq!(formatted, "Hallo Anna");
+    }
+
+    #[test]
+    fn arg_aliased() {
+        let format = |tpl: &str| tpl.to_format().arg_aliased("name", 0, &"Anna").format();
+
+        assert_eq!(format("Hallo {0}"), "Hallo Anna");
+        assert_eq!(format("Hallo {name}"), "Hallo Anna");
+        assert_eq!(format("Hallo {1}"), "Hallo {1}");
+        assert_eq!(
+            "{0} und {name}"
+                .to_format()
+                .arg_aliased("name", 0, &"Anna")
+                .isolate_args(true)
+                .format(),
+            "\u{2068}Anna\u{2069} und \u{2068}Anna\u{2069}"
+        );
+    }
+
+    #[test]
+    fn int_parts() {
+        let parts = format_int_parts(-1234567, Locale::de_DE);
+        assert_eq!(
+            parts,
+            NumberParts {
+                sign: "-",
+                groups: vec!["1".to_owned(), "234".to_owned(), "567".to_owned()],
+                separator: ".",
+            }
+        );
+        assert_eq!(parts.to_string(), format_int(-1234567, Locale::de_DE));
+
+        let parts = format_int_parts(123456u32, Locale::en_GB);
+        assert_eq!(parts.sign, "");
+        assert_eq!(parts.groups, ["123", "456"]);
+        assert_eq!(parts.to_string(), "123,456");
+        assert_eq!(format_int_parts(0, Locale::fr_FR).groups, ["0"]);
+        assert_eq!(
+            format_int_parts(u128::MAX, Locale::de_DE).to_string(),
+            format_int(u128::MAX, Locale::de_DE)
+        );
+    }
+
+    #[test]
+    fn mirror_for() {
+        for locale in ALL_LOCALES {
+            assert!(!locale.is_rtl());
+            let formatted = "({count}) [Entwurf]"
+                .to_format()
+                .arg("count", &3)
+                .mirror_for(locale)
+                .format();
+            assert_eq!(formatted, "(3) [Entwurf]");
+        }
+
+        // No RTL locale is supported yet, so check the parts used for them.
+        assert!(is_rtl_script("Arab"));
+        assert!(is_rtl_script("Hebr"));
+        assert!(!is_rtl_script("Latn"));
+        assert_eq!(mirror_brackets("({count}) [draft]"), "){count}( ]draft[");
+    }
+
+    #[test]
+    fn format_error() {
+        let mut builder = "Hallo {name}, du hast {count} Nachrichten".to_format();
+        builder.arg("count", &3);
+        assert_eq!(
+            builder.try_format(),
+            Err(FormatError::MissingKey("name".to_owned()))
+        );
+        assert_eq!(
+            "Hallo {0}".to_format().try_format(),
+            Err(FormatError::MissingKey("0".to_owned()))
+        );
+
+        builder.arg("name", &"Anna");
+        assert_eq!(
+            builder.try_format().unwrap(),
+            "Hallo Anna, du hast 3 Nachrichten"
+        );
+
+        // The result only borrows the template, not the builder.
+        let name = "Anna".to_owned();
+        let formatted = "Hallo {name}".to_format().arg("name", &name).try_format();
+        assert_eq!(formatted.unwrap(), "Hallo Anna");
+        let formatted = "{n, plural, one {# Datei} other {# Dateien}}"
+            .to_format()
+            .plural("n", 2, Locale::de_DE)
+            .try_format();
+        assert_eq!(formatted.unwrap(), "2 Dateien");
+    }
+
+    #[test]
+    fn write_to() {
+        let mut builder = "{name} hat {count} Nachrichten".to_format();
+        builder.arg("name", &"Anna").arg("count", &3);
+        let mut written = Vec::new();
+        builder.write_to(&mut written).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), builder.format());
+
+        let builder = "Hallo {name}".to_format();
+        let mut written = Vec::new();
+        builder.write_to(&mut written).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), "Hallo {name}");
+    }
+
+    #[test]
+    fn guess_locale() {
+        let candidates = [Locale::en_GB, Locale::de_DE];
+
+        assert_eq!(
+            guess_number_locale("1.234,56", &candidates),
+            Some(Locale::de_DE)
+        );
+        assert_eq!(
+            guess_number_locale("1,234.56", &candidates),
+            Some(Locale::en_GB)
+        );
+        assert_eq!(
+            guess_number_locale("1234,5", &candidates),
+            Some(Locale::de_DE)
+        );
+        assert_eq!(guess_number_locale("1.234", &candidates), None);
+        assert_eq!(guess_number_locale("1,234", &candidates), None);
+        assert_eq!(
+            guess_number_locale("1234", &candidates),
+            Some(Locale::en_GB)
+        );
+        assert_eq!(guess_number_locale("abc", &candidates), None);
+        assert_eq!(
+            guess_number_locale("1.234", &[Locale::de_DE]),
+            Some(Locale::de_DE)
+        );
+    }
+
+    #[test]
+    fn int_cow() {
+        for locale in ALL_LOCALES {
+            for n in (-20_000..20_000)
+                .step_by(7)
+                .chain([0, 9, 10, i128::MIN, i128::MAX])
+            {
+                assert_eq!(format_int_cow(n, locale), format_int(n, locale));
+            }
+        }
+
+        assert!(matches!(
+            format_int_cow(7u8, Locale::de_DE),
+            borrow::Cow::Borrowed("7")
+        ));
+        assert!(matches!(
+            format_int_cow(5usize, Locale::de_DE),
+            borrow::Cow::Borrowed("5")
+        ));
+        assert_eq!(format_int_cow(-5isize, Locale::de_DE), "-5");
+        assert_eq!(
+            format_int_cow(u128::MAX, Locale::de_DE),
+            format_int(u128::MAX, Locale::de_DE)
+        );
+        assert!(matches!(
+            format_int_cow(10, Locale::de_DE),
+            borrow::Cow::Owned(_)
+        ));
+    }
+
+    #[test]
+    fn custom_separators() {
+        assert_eq!(format_f64_custom(1234.567, 2, '.', '\''), "1'234.57");
+        assert_eq!(format_f64_custom(-1234567, 0, ',', '\''), "-1'234'567");
+        assert_eq!(
+            format_f64_custom(1234.5, 1, '·', '\u{202f}'),
+            "1\u{202f}234·5"
+        );
+    }
+
+    #[test]
+    fn fallback_from_env() {
+        let new = |value: Option<&str>| {
+            let catalogs = HashMap::from([(Locale::de_DE, Catalog::empty())]);
+            Localizer::new_from_var(catalogs, value.map(str::to_owned))
+        };
+
+        let localizer = new(Some("de_DE")).ok().unwrap();
+        assert_eq!(localizer.fallback(), Locale::de_DE);
+        assert_eq!(new(None).err(), Some(FallbackFromEnvError::Unset));
+        assert_eq!(
+            new(Some("xx_XX")).err(),
+            Some(FallbackFromEnvError::Invalid("xx_XX".to_owned()))
+        );
+        assert_eq!(
+            new(Some("en_GB")).err(),
+            Some(FallbackFromEnvError::Missing(Locale::en_GB))
+        );
+    }
+
+    #[test]
+    fn reference() {
+        assert_eq!(format_reference(20240815, Locale::de_DE), "20240815");
+        assert_eq!(format_reference(20240815usize, Locale::de_DE), "20240815");
+        for locale in ALL_LOCALES {
+            let minus = locale.num_format_locale().minus_sign();
+            assert_eq!(
+                format_reference(-1234567, locale),
+                format!("{}1234567", minus)
+            );
+        }
+    }
+
+    #[test]
+    fn merge() {
+        let localizer = |entries: &[(Locale, &str)]| {
+            let catalogs = entries
+                .iter()
+                .map(|&(locale, yes)| (locale, catalog(&[("Yes", yes)])))
+                .collect();
+            Localizer::new(catalogs, entries[0].0).unwrap()
+        };
+        let base = || localizer(&[(Locale::en_GB, "Yes"), (Locale::de_DE, "Ja")]);
+        let plugin = || localizer(&[(Locale::fr_FR, "Oui"), (Locale::de_DE, "Jawohl")]);
+
+        let mut merged = base();
+        merged.merge(plugin(), ConflictPolicy::KeepSelf).unwrap();
+        assert_eq!(merged.fallback(), Locale::en_GB);
+        assert_eq!(merged.get_catalog(Locale::fr_FR).gettext("Yes"), "Oui");
+        assert_eq!(merged.get_catalog(Locale::de_DE).gettext("Yes"), "Ja");
+
+        let mut merged = base();
+        merged.merge(plugin(), ConflictPolicy::TakeOther).unwrap();
+        assert_eq!(merged.get_catalog(Locale::fr_FR).gettext("Yes"), "Oui");
+        assert_eq!(merged.get_catalog(Locale::de_DE).gettext("Yes"), "Jawohl");
+
+        let mut merged = base();
+        let err = merged.merge(plugin(), ConflictPolicy::Error).unwrap_err();
+        assert_eq!(err.0, vec![Locale::de_DE]);
+        assert!(!merged.contains(Locale::fr_FR));
+    }
+
+    #[test]
+    fn progress() {
+        assert_eq!(format_of(3, 10, Locale::de_DE), "3 von 10");
+        assert_eq!(format_of(1500, 2000, Locale::de_DE), "1.500 von 2.000");
+        assert_eq!(format_of(3, 10, Locale::fr_FR), "3 sur 10");
+        assert_eq!(
+            format_of(1500, 2000, Locale::fr_FR),
+            "1\u{202f}500 sur 2\u{202f}000"
+        );
+        assert_eq!(format_of(3, 10, Locale::en_GB), "3 of 10");
+    }
+
+    #[test]
+    fn validate_template() {
+        let validate = FormatBuilder::validate_template;
+
+        assert_eq!(
+            validate("Hallo {name}, du hast {count_2} Nachrichten"),
+            Ok(())
+        );
+        assert_eq!(validate("Keine Platzhalter"), Ok(()));
+        assert_eq!(
+            validate("{n, plural, one {# Datei von {user}} other {# Dateien}}"),
+            Ok(())
+        );
+        assert_eq!(validate("Hallo {name"), Err(TemplateError::Unclosed(6)));
+        assert_eq!(validate("Hallo name}"), Err(TemplateError::Unmatched(10)));
+        assert_eq!(
+            validate("Hallo {}"),
+            Err(TemplateError::EmptyPlaceholder(6))
+        );
+        assert_eq!(
+            validate("Hallo {first name}"),
+            Err(TemplateError::InvalidPlaceholder(6))
+        );
+        assert_eq!(validate("{größe} cm"), Ok(()));
+        assert_eq!("{größe} cm".to_format().arg("größe", &3).format(), "3 cm");
+        assert_eq!(
+            validate("Preis in {€}"),
+            Err(TemplateError::InvalidPlaceholder(9))
+        );
+        assert_eq!(
+            validate("Hallo {\u{a0}}"),
+            Err(TemplateError::InvalidPlaceholder(6))
+        );
+        assert_eq!(
+            validate("{n, plural, one {# Datei}"),
+            Err(TemplateError::Unclosed(0))
+        );
+    }
+
+    #[test]
+    fn negotiate() {
+        let catalogs =
+            HashMap::from([(Locale::de_DE, catalog(&[])), (Locale::fr_FR, catalog(&[]))]);
+        let localizer = Localizer::new(catalogs, Locale::fr_FR).unwrap();
+
+        assert_eq!(localizer.negotiate("de-AT, en;q=0.8"), Locale::de_DE);
+        assert_eq!(localizer.negotiate("en;q=0.5, de;q=0.9"), Locale::de_DE);
+        assert_eq!(localizer.negotiate("en-GB, es;q=0.9"), Locale::fr_FR);
+        assert_eq!(localizer.negotiate("de;q=0, *"), Locale::fr_FR);
+        assert_eq!(localizer.negotiate(""), Locale::fr_FR);
+        assert_eq!(
+            super::negotiate("ru, en-US;q=0.7", &[Locale::en_GB]),
+            Some(Locale::en_GB)
+        );
+    }
+
+    #[test]
+    fn catalog_with() {
+        let catalogs = HashMap::from([
+            (Locale::de_DE, catalog(&[("Yes", "Ja")])),
+            (Locale::es_ES, catalog(&[("Yes", "Sí")])),
+            (Locale::fr_FR, catalog(&[("Yes", "Oui")])),
+        ]);
+        let localizer = Localizer::new(catalogs, Locale::fr_FR).unwrap();
+        let to_spanish = |locale| match locale {
+            Locale::pt_PT => Locale::es_ES,
+            _ => Locale::it_IT,
+        };
+
+        let get = |locale| {
+            localizer
+                .get_catalog_with(locale, to_spanish)
+                .gettext("Yes")
+        };
+        assert_eq!(get(Locale::de_DE), "Ja");
+        assert_eq!(get(Locale::pt_PT), "Sí");
+        assert_eq!(get(Locale::ru_RU), "Oui");
+    }
+
+    #[test]
+    fn catalog_by_str() {
+        let catalogs = HashMap::from([
+            (Locale::de_DE, catalog(&[("Yes", "Ja")])),
+            (Locale::fr_FR, catalog(&[("Yes", "Oui")])),
+        ]);
+        let localizer = Localizer::new(catalogs, Locale::fr_FR).unwrap();
+
+        assert_eq!(localizer.get_catalog_str("de-DE").gettext("Yes"), "Ja");
+        assert_eq!(localizer.get_catalog_str("DE").gettext("Yes"), "Ja");
+        assert_eq!(localizer.get_catalog_str("de-AT").gettext("Yes"), "Ja");
+        assert_eq!(localizer.get_catalog_str("en-GB").gettext("Yes"), "Oui");
+        assert_eq!(
+            localizer.get_catalog_str("%&/ garbage").gettext("Yes"),
+            "Oui"
+        );
+        assert_eq!(localizer.get_catalog_str("").gettext("Yes"), "Oui");
+    }
 }
@@ -0,0 +1,228 @@
+//! The static data of all supported locales.
+
+use crate::Locale;
+
+/// The data of a [Locale] used by its conversions and accessors.
+pub(crate) struct LocaleData {
+    pub locale: Locale,
+    /// The canonical code, e.g. `"de_DE"`.
+    pub code: &'static str,
+    /// The language code, e.g. `"de"`.
+    pub language: &'static str,
+    /// The BCP 47 language tag, e.g. `"de-DE"`.
+    pub html_lang: &'static str,
+    /// The POSIX locale name with charset, e.g. `"de_DE.UTF-8"`.
+    pub posix: &'static str,
+    /// The ISO 15924 script code, e.g. `"Latn"`.
+    pub script: &'static str,
+    pub num_format: num_format::Locale,
+    #[cfg(feature = "chrono")]
+    pub chrono: chrono::Locale,
+    /// The number of plural forms gettext uses.
+    pub plural_forms: usize,
+    /// The placeholder for missing data.
+    pub not_available: &'static str,
+    /// The word between the numbers of progress strings like "3 of 10".
+    pub of: &'static str,
+    /// The abbreviation of percentage points, e.g. `"Pp."`.
+    pub percentage_points: &'static str,
+    /// The separator between compact counts and their suffix, e.g. a no-break space.
+    pub compact_separator: &'static str,
+    /// The thousands, millions and billions suffixes of compact counts, e.g. `"Tsd."`.
+    pub compact_suffixes: [&'static str; 3],
+    /// The space between a number and the percent sign, e.g. a no-break space in German.
+    pub percent_spacing: &'static str,
+    /// The words between a rating and its maximum like "4.5 out of 5". Unlike `of` for progress,
+    /// ratings are phrased as a share of the maximum in some languages, e.g. "out of" in English
+    /// and "su" instead of "di" in Italian.
+    pub out_of: &'static str,
+}
+
+/// The data of all locales in the order of the [Locale] variants.
+pub(crate) const LOCALE_DATA: [LocaleData; 7] = [
+    LocaleData {
+        locale: Locale::de_DE,
+        code: "de_DE",
+        language: "de",
+        html_lang: "de-DE",
+        posix: "de_DE.UTF-8",
+        script: "Latn",
+        num_format: num_format::Locale::de,
+        #[cfg(feature = "chrono")]
+        chrono: chrono::Locale::de_DE,
+        plural_forms: 2,
+        not_available: "k. A.",
+        of: "von",
+        percentage_points: "Pp.",
+        compact_separator: "\u{a0}",
+        compact_suffixes: ["Tsd.", "Mio.", "Mrd."],
+        percent_spacing: "\u{a0}",
+        out_of: "von",
+    },
+    LocaleData {
+        locale: Locale::en_GB,
+        code: "en_GB",
+        language: "en",
+        html_lang: "en-GB",
+        posix: "en_GB.UTF-8",
+        script: "Latn",
+        num_format: num_format::Locale::en_GB,
+        #[cfg(feature = "chrono")]
+        chrono: chrono::Locale::en_GB,
+        plural_forms: 2,
+        not_available: "N/A",
+        of: "of",
+        percentage_points: "pp",
+        compact_separator: "",
+        compact_suffixes: ["K", "M", "B"],
+        percent_spacing: "",
+        out_of: "out of",
+    },
+    LocaleData {
+        locale: Locale::es_ES,
+        code: "es_ES",
+        language: "es",
+        html_lang: "es-ES",
+        posix: "es_ES.UTF-8",
+        script: "Latn",
+        num_format: num_format::Locale::es,
+        #[cfg(feature = "chrono")]
+        chrono: chrono::Locale::es_ES,
+        plural_forms: 2,
+        not_available: "N/D",
+        of: "de",
+        percentage_points: "p. p.",
+        compact_separator: "\u{a0}",
+        compact_suffixes: ["mil", "M", "mil\u{a0}M"],
+        percent_spacing: "\u{a0}",
+        out_of: "de",
+    },
+    LocaleData {
+        locale: Locale::fr_FR,
+        code: "fr_FR",
+        language: "fr",
+        html_lang: "fr-FR",
+        posix: "fr_FR.UTF-8",
+        script: "Latn",
+        num_format: num_format::Locale::fr,
+        #[cfg(feature = "chrono")]
+        chrono: chrono::Locale::fr_FR,
+        plural_forms: 2,
+        not_available: "n.d.",
+        of: "sur",
+        percentage_points: "pts",
+        compact_separator: "\u{a0}",
+        compact_suffixes: ["k", "M", "Md"],
+        percent_spacing: "\u{202f}",
+        out_of: "sur",
+    },
+    LocaleData {
+        locale: Locale::it_IT,
+        code: "it_IT",
+        language: "it",
+        html_lang: "it-IT",
+        posix: "it_IT.UTF-8",
+        script: "Latn",
+        num_format: num_format::Locale::it,
+        #[cfg(feature = "chrono")]
+        chrono: chrono::Locale::it_IT,
+        plural_forms: 2,
+        not_available: "N/D",
+        of: "di",
+        percentage_points: "p.p.",
+        compact_separator: "\u{a0}",
+        compact_suffixes: ["mila", "Mln", "Mrd"],
+        percent_spacing: "",
+        out_of: "su",
+    },
+    LocaleData {
+        locale: Locale::pt_PT,
+        code: "pt_PT",
+        language: "pt",
+        html_lang: "pt-PT",
+        posix: "pt_PT.UTF-8",
+        script: "Latn",
+        num_format: num_format::Locale::pt,
+        #[cfg(feature = "chrono")]
+        chrono: chrono::Locale::pt_PT,
+        plural_forms: 2,
+        not_available: "N/D",
+        of: "de",
+        percentage_points: "p.p.",
+        compact_separator: "\u{a0}",
+        compact_suffixes: ["mil", "M", "mM"],
+        percent_spacing: "",
+        out_of: "de",
+    },
+    LocaleData {
+        locale: Locale::ru_RU,
+        code: "ru_RU",
+        language: "ru",
+        html_lang: "ru-RU",
+        posix: "ru_RU.UTF-8",
+        script: "Cyrl",
+        num_format: num_format::Locale::ru,
+        #[cfg(feature = "chrono")]
+        chrono: chrono::Locale::ru_RU,
+        plural_forms: 3,
+        not_available: "н/д",
+        of: "из",
+        percentage_points: "п. п.",
+        compact_separator: "\u{a0}",
+        compact_suffixes: ["тыс.", "млн", "млрд"],
+        percent_spacing: "\u{a0}",
+        out_of: "из",
+    },
+];
+
+// Indexing the table by variant requires it to be in the order of the variants.
+const _: () = {
+    let mut i = 0;
+    while i < LOCALE_DATA.len() {
+        assert!(LOCALE_DATA[i].locale as usize == i);
+        i += 1;
+    }
+};
+
+impl Locale {
+    /// Returns the [LocaleData] of the [Locale].
+    pub(crate) fn data(&self) -> &'static LocaleData {
+        // The exhaustive match makes adding a variant without its data a compile error, since the
+        // index of a new arm is out of bounds of the table.
+        match self {
+            Locale::de_DE => &LOCALE_DATA[0],
+            Locale::en_GB => &LOCALE_DATA[1],
+            Locale::es_ES => &LOCALE_DATA[2],
+            Locale::fr_FR => &LOCALE_DATA[3],
+            Locale::it_IT => &LOCALE_DATA[4],
+            Locale::pt_PT => &LOCALE_DATA[5],
+            Locale::ru_RU => &LOCALE_DATA[6],
+        }
+    }
+}
+
+/// All supported locales in the order of the [Locale] variants.
+pub(crate) const ALL_LOCALES: [Locale; LOCALE_DATA.len()] = {
+    let mut locales = [Locale::de_DE; LOCALE_DATA.len()];
+    let mut i = 0;
+    while i < LOCALE_DATA.len() {
+        locales[i] = LOCALE_DATA[i].locale;
+        i += 1;
+    }
+    locales
+};
+
+#[cfg(test)]
+mod tests {
+    use super::ALL_LOCALES;
+
+    #[test]
+    fn covers_all_locales() {
+        for locale in ALL_LOCALES {
+            let data = locale.data();
+            assert_eq!(data.locale, locale);
+            assert!(data.code.starts_with(data.language));
+            assert_eq!(data.html_lang, data.code.replace('_', "-"));
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Access to the header entries of catalogs.
+
+use crate::{CatalogLike, Locale, Localizer};
+
+/// The header entries of a catalog, e.g. to show who last translated it and when.
+///
+/// Each field is `None` if the header is missing or empty.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CatalogMetadata {
+    /// The `Project-Id-Version` header, e.g. `"app 1.0"`.
+    pub project_id_version: Option<String>,
+    /// The `Language` header, e.g. `"de"`.
+    pub language: Option<String>,
+    /// The `Language-Team` header, e.g. `"German <de@example.com>"`.
+    pub language_team: Option<String>,
+    /// The `Last-Translator` header, e.g. `"Anna <anna@example.com>"`.
+    pub last_translator: Option<String>,
+    /// The `PO-Revision-Date` header, e.g. `"2024-05-12 14:30+0200"`.
+    pub po_revision_date: Option<String>,
+}
+
+impl CatalogMetadata {
+    /// Parses the header entries of `catalog`.
+    pub fn from_catalog(catalog: &impl CatalogLike) -> Self {
+        // gettext returns the header block as the translation of the empty msgid.
+        let mut metadata = CatalogMetadata::default();
+        for line in catalog.gettext("").lines() {
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            let field = match name.trim() {
+                "Project-Id-Version" => &mut metadata.project_id_version,
+                "Language" => &mut metadata.language,
+                "Language-Team" => &mut metadata.language_team,
+                "Last-Translator" => &mut metadata.last_translator,
+                "PO-Revision-Date" => &mut metadata.po_revision_date,
+                _ => continue,
+            };
+            let value = value.trim();
+            *field = (!value.is_empty()).then(|| value.to_owned());
+        }
+        metadata
+    }
+}
+
+impl<C: CatalogLike> Localizer<C> {
+    /// Returns the header entries of the catalog for `locale`, or `None` if there is no catalog.
+    pub fn metadata(&self, locale: impl Into<Locale>) -> Option<CatalogMetadata> {
+        self.catalogs
+            .get(&locale.into())
+            .map(CatalogMetadata::from_catalog)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CatalogMetadata;
+    use crate::test_support::catalog;
+    use crate::{Locale, Localizer};
+    use std::collections::HashMap;
+
+    #[test]
+    fn metadata() {
+        let header = "Project-Id-Version: app 1.0\n\
+            PO-Revision-Date: 2024-05-12 14:30+0200\n\
+            Last-Translator: Anna <anna@example.com>\n\
+            Language-Team: German <de@example.com>\n\
+            Language: \n\
+            Content-Type: text/plain; charset=UTF-8\n";
+        let catalogs = HashMap::from([(Locale::de_DE, catalog(&[("", header)]))]);
+        let localizer = Localizer::new(catalogs, Locale::de_DE).unwrap();
+
+        assert_eq!(
+            localizer.metadata(Locale::de_DE),
+            Some(CatalogMetadata {
+                project_id_version: Some("app 1.0".to_owned()),
+                language: None,
+                language_team: Some("German <de@example.com>".to_owned()),
+                last_translator: Some("Anna <anna@example.com>".to_owned()),
+                po_revision_date: Some("2024-05-12 14:30+0200".to_owned()),
+            })
+        );
+        assert_eq!(localizer.metadata(Locale::fr_FR), None);
+    }
+}
@@ -0,0 +1,388 @@
+//! Loading of catalogs from directories of MO files.
+
+use crate::{Locale, Localizer, MissingFallbackError};
+use gettext::Catalog;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+use thiserror::Error;
+
+impl Localizer<Catalog> {
+    /// Creates a new `Localizer` from the MO files named after their locale in `dir`, e.g.
+    /// `de_DE.mo`, with the given fallback locale.
+    ///
+    /// Files without the `mo` extension or with a name which is no [Locale] are skipped. Fails with
+    /// [MoDirError::Charset] if a file declares another charset than UTF-8, whose translations
+    /// would be garbled in the output.
+    pub fn from_mo_dir(dir: impl AsRef<Path>, fallback: Locale) -> Result<Self, MoDirError> {
+        Self::load_mo_dir(dir.as_ref(), fallback, false)
+    }
+
+    /// Like [Localizer::from_mo_dir] but normalizes the whitespace of all translations while
+    /// loading.
+    ///
+    /// Runs of ASCII spaces are collapsed to a single space and trailing ASCII whitespace is
+    /// removed from each plural form. No-break spaces are kept. The header is not changed.
+    pub fn from_mo_dir_normalized(
+        dir: impl AsRef<Path>,
+        fallback: Locale,
+    ) -> Result<Self, MoDirError> {
+        Self::load_mo_dir(dir.as_ref(), fallback, true)
+    }
+
+    /// Creates a new `Localizer` from the MO files named after their locale in the embedded
+    /// directory `dir`, e.g. from `include_dir!("$CARGO_MANIFEST_DIR/locales")`, with the given
+    /// fallback locale.
+    ///
+    /// Files are selected and checked like in [Localizer::from_mo_dir]. Subdirectories are not
+    /// searched.
+    #[cfg(feature = "include_dir")]
+    pub fn from_include_dir(
+        dir: &include_dir::Dir<'_>,
+        fallback: Locale,
+    ) -> Result<Self, MoDirError> {
+        let mut catalogs = HashMap::new();
+        for file in dir.files() {
+            if let Some(locale) = mo_locale(file.path()) {
+                let catalog = parse_mo(file.path(), file.contents().to_vec(), false)?;
+                catalogs.insert(locale, catalog);
+            }
+        }
+
+        Ok(Self::new(catalogs, fallback)?)
+    }
+
+    /// Implements [Localizer::from_mo_dir] and [Localizer::from_mo_dir_normalized].
+    fn load_mo_dir(dir: &Path, fallback: Locale, normalize: bool) -> Result<Self, MoDirError> {
+        let io_error = |path: &Path| {
+            let path = path.to_owned();
+            move |err| MoDirError::Io(path, err)
+        };
+
+        let mut catalogs = HashMap::new();
+        for file in fs::read_dir(dir).map_err(io_error(dir))? {
+            let path = file.map_err(io_error(dir))?.path();
+            let Some(locale) = mo_locale(&path) else {
+                continue;
+            };
+
+            let mo = fs::read(&path).map_err(io_error(&path))?;
+            catalogs.insert(locale, parse_mo(&path, mo, normalize)?);
+        }
+
+        Ok(Self::new(catalogs, fallback)?)
+    }
+}
+
+/// Exports the translations of the MO file at `path` as a JSON object for JavaScript i18n
+/// libraries, e.g. `{"Yes": "Ja", "file": ["Datei", "Dateien"]}`.
+///
+/// Singular messages map to their translation and plural messages map from their singular
+/// msgid to an array of all plural forms. Messages with a context are keyed by the context and
+/// the msgid separated by `\u0004`, as in MO files. The header is not exported. The file is
+/// checked like in [Localizer::from_mo_dir].
+#[cfg(feature = "serde_json")]
+pub fn export_mo_json(path: impl AsRef<Path>) -> Result<String, MoDirError> {
+    let path = path.as_ref();
+    let mo = fs::read(path).map_err(|err| MoDirError::Io(path.to_owned(), err))?;
+    let invalid = |err| MoDirError::Parse(path.to_owned(), err);
+    parse_mo(path, mo.clone(), false)?;
+    let entries = read_mo(&mo).ok_or_else(|| invalid(gettext::Error::Eof))?;
+
+    let mut json = serde_json::Map::new();
+    for (msgid, msgstr) in entries.into_iter().filter(|(msgid, _)| !msgid.is_empty()) {
+        let decode = |s: &[u8]| {
+            String::from_utf8(s.to_vec()).map_err(|_| invalid(gettext::Error::DecodingError))
+        };
+        let msgid = decode(msgid)?;
+        // Plural msgids are stored as `singular\0plural`.
+        let (key, plural) = match msgid.split_once('\0') {
+            Some((singular, _)) => (singular.to_owned(), true),
+            None => (msgid, false),
+        };
+        let msgstr = decode(msgstr)?;
+        let value = if plural {
+            msgstr.split('\0').map(serde_json::Value::from).collect()
+        } else {
+            serde_json::Value::String(msgstr)
+        };
+        json.insert(key, value);
+    }
+    Ok(serde_json::Value::Object(json).to_string())
+}
+
+/// Returns the locale of an MO file named after it like `de_DE.mo`, or `None` for other files.
+pub(crate) fn mo_locale(path: &Path) -> Option<Locale> {
+    if path.extension()? != "mo" {
+        return None;
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+/// Parses the contents `mo` of the MO file at `path` for [Localizer::from_mo_dir], normalizing
+/// its translations if `normalize` is set.
+fn parse_mo(path: &Path, mut mo: Vec<u8>, normalize: bool) -> Result<Catalog, MoDirError> {
+    if normalize {
+        // Invalid files are left for gettext to report.
+        if let Some(normalized) = normalize_mo(&mo) {
+            mo = normalized;
+        }
+    }
+    let catalog = Catalog::parse(&mo[..]).map_err(|err| MoDirError::Parse(path.to_owned(), err))?;
+    if let Some(charset) = declared_charset(&catalog) {
+        if !charset.eq_ignore_ascii_case("UTF-8") && !charset.eq_ignore_ascii_case("UTF8") {
+            return Err(MoDirError::Charset(path.to_owned(), charset));
+        }
+    }
+    Ok(catalog)
+}
+
+/// Describes why catalogs could not be loaded by [Localizer::from_mo_dir] or
+/// `Localizer::from_include_dir`.
+#[derive(Debug, Error)]
+pub enum MoDirError {
+    /// The directory or a file could not be read.
+    #[error("Could not read {0:?}: {1}")]
+    Io(PathBuf, #[source] io::Error),
+    /// A file is no valid MO file.
+    #[error("Invalid MO file {0:?}: {1}")]
+    Parse(PathBuf, #[source] gettext::Error),
+    /// A file declares a charset other than UTF-8.
+    #[error("MO file {0:?} has the charset {1}, but UTF-8 is required")]
+    Charset(PathBuf, String),
+    /// There is no MO file for the fallback locale.
+    #[error(transparent)]
+    MissingFallback(#[from] MissingFallbackError),
+}
+
+/// Reads the charset from the `Content-Type` header of `catalog` if there is one.
+fn declared_charset(catalog: &Catalog) -> Option<String> {
+    // gettext returns the header block as the translation of the empty msgid.
+    catalog
+        .gettext("")
+        .lines()
+        .find_map(|line| line.strip_prefix("Content-Type:"))?
+        .split(';')
+        .find_map(|part| part.trim().strip_prefix("charset="))
+        .map(|charset| charset.trim().to_owned())
+}
+
+/// Builds the bytes of an MO file containing `entries` as msgid/msgstr pairs without a hash
+/// table.
+pub(crate) fn write_mo(entries: &[(&[u8], &[u8])]) -> Vec<u8> {
+    let mut entries = entries.to_vec();
+    entries.sort();
+
+    let header_len = 28 + 16 * entries.len();
+    let mut tables = Vec::new();
+    let mut strings = Vec::new();
+    for column in [0, 1] {
+        for entry in &entries {
+            let s = if column == 0 { entry.0 } else { entry.1 };
+            tables.extend((s.len() as u32).to_le_bytes());
+            tables.extend(((header_len + strings.len()) as u32).to_le_bytes());
+            strings.extend(s);
+            strings.push(0);
+        }
+    }
+
+    let mut mo = Vec::new();
+    for n in [
+        0x950412de,
+        0,
+        entries.len(),
+        28,
+        28 + 8 * entries.len(),
+        0,
+        0,
+    ] {
+        mo.extend((n as u32).to_le_bytes());
+    }
+    mo.extend(tables);
+    mo.extend(strings);
+    mo
+}
+
+/// Reads the msgid/msgstr pairs of the MO file `mo` as written by [write_mo].
+///
+/// Returns `None` if `mo` is invalid.
+pub(crate) fn read_mo(mo: &[u8]) -> Option<Vec<(&[u8], &[u8])>> {
+    let read_u32: fn([u8; 4]) -> u32 = match mo.get(..4)? {
+        [0xde, 0x12, 0x04, 0x95] => u32::from_le_bytes,
+        [0x95, 0x04, 0x12, 0xde] => u32::from_be_bytes,
+        _ => return None,
+    };
+    let word = |offset: usize| -> Option<usize> {
+        let bytes = mo.get(offset..offset + 4)?;
+        Some(read_u32(bytes.try_into().ok()?) as usize)
+    };
+    let string = |table: usize, i: usize| -> Option<&[u8]> {
+        let len = word(table + 8 * i)?;
+        let offset = word(table + 8 * i + 4)?;
+        mo.get(offset..offset.checked_add(len)?)
+    };
+
+    let count = word(8)?;
+    let originals = word(12)?;
+    let translations = word(16)?;
+    (0..count)
+        .map(|i| Some((string(originals, i)?, string(translations, i)?)))
+        .collect()
+}
+
+/// Rewrites the MO file `mo` with the whitespace of all translations normalized as described in
+/// [Localizer::from_mo_dir_normalized].
+fn normalize_mo(mo: &[u8]) -> Option<Vec<u8>> {
+    let entries = read_mo(mo)?;
+    let translations: Vec<Vec<u8>> = entries
+        .iter()
+        .map(|&(msgid, msgstr)| {
+            if msgid.is_empty() {
+                return msgstr.to_vec();
+            }
+            let forms: Vec<Vec<u8>> = msgstr.split(|&b| b == 0).map(normalize_form).collect();
+            forms.join(&0)
+        })
+        .collect();
+    let entries: Vec<(&[u8], &[u8])> = entries
+        .iter()
+        .zip(&translations)
+        .map(|(&(msgid, _), msgstr)| (msgid, &msgstr[..]))
+        .collect();
+    Some(write_mo(&entries))
+}
+
+/// Collapses runs of ASCII spaces in `form` and removes trailing ASCII whitespace.
+///
+/// Works on bytes, so it is independent of the charset as long as it is a superset of ASCII.
+fn normalize_form(form: &[u8]) -> Vec<u8> {
+    let mut normalized = Vec::with_capacity(form.len());
+    for &b in form {
+        if !(b == b' ' && normalized.last() == Some(&b' ')) {
+            normalized.push(b);
+        }
+    }
+    while normalized.last().map_or(false, u8::is_ascii_whitespace) {
+        normalized.pop();
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MoDirError;
+    use crate::test_support::mo_file;
+    use crate::{Locale, Localizer};
+    use std::{env, fs, process};
+
+    #[test]
+    fn charset() {
+        let dir = env::temp_dir().join(format!("getprose-mo-charset-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let header = |charset| format!("Content-Type: text/plain; charset={}\n", charset);
+        let mo = mo_file(&[("", &header("UTF-8")), ("Yes", "Ja")]);
+        fs::write(dir.join("de_DE.mo"), mo).unwrap();
+
+        let utf8 = Localizer::from_mo_dir(&dir, Locale::de_DE);
+        fs::write(
+            dir.join("fr_FR.mo"),
+            mo_file(&[("", &header("ISO-8859-1")), ("Yes", "Oui")]),
+        )
+        .unwrap();
+        let latin1 = Localizer::from_mo_dir(&dir, Locale::de_DE);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            utf8.unwrap().get_catalog(Locale::de_DE).gettext("Yes"),
+            "Ja"
+        );
+        match latin1 {
+            Err(MoDirError::Charset(path, charset)) => {
+                assert!(path.ends_with("fr_FR.mo"));
+                assert_eq!(charset, "ISO-8859-1");
+            }
+            _ => panic!("expected a charset error"),
+        }
+    }
+
+    #[test]
+    fn normalized() {
+        let dir = env::temp_dir().join(format!("getprose-mo-dir-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mo = mo_file(&[
+            ("Hello world", "Hallo  Welt  "),
+            ("{} kg", "{}\u{a0}\u{a0}kg"),
+            ("{} file", "{}  Datei \0{}  Dateien\n"),
+        ]);
+        fs::write(dir.join("de_DE.mo"), mo).unwrap();
+        fs::write(dir.join("README.txt"), "not a catalog").unwrap();
+
+        let normalized = Localizer::from_mo_dir_normalized(&dir, Locale::de_DE);
+        let unchanged = Localizer::from_mo_dir(&dir, Locale::de_DE);
+        let missing = Localizer::from_mo_dir(&dir, Locale::fr_FR);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let normalized = normalized.unwrap();
+        let catalog = normalized.get_catalog(Locale::de_DE);
+        assert_eq!(catalog.gettext("Hello world"), "Hallo Welt");
+        assert_eq!(catalog.gettext("{} kg"), "{}\u{a0}\u{a0}kg");
+        assert_eq!(catalog.ngettext("{} file", "{} files", 1), "{} Datei");
+        assert_eq!(catalog.ngettext("{} file", "{} files", 2), "{} Dateien");
+        let unchanged = unchanged.unwrap();
+        assert_eq!(
+            unchanged.get_catalog(Locale::de_DE).gettext("Hello world"),
+            "Hallo  Welt  "
+        );
+        assert!(matches!(missing, Err(MoDirError::MissingFallback(_))));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn export_json() {
+        let dir = env::temp_dir().join(format!("getprose-mo-json-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("de_DE.mo");
+        fs::write(
+            &path,
+            mo_file(&[
+                ("", "Content-Type: text/plain; charset=UTF-8\n"),
+                ("Yes", "Ja"),
+                ("file\0files", "Datei\0Dateien"),
+                ("menu\u{4}Open", "Öffnen"),
+            ]),
+        )
+        .unwrap();
+        let json = super::export_mo_json(&path);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&json.unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "Yes": "Ja",
+                "file": ["Datei", "Dateien"],
+                "menu\u{4}Open": "Öffnen",
+            })
+        );
+    }
+
+    #[cfg(feature = "include_dir")]
+    #[test]
+    fn include_dir() {
+        static LOCALES: include_dir::Dir<'_> =
+            include_dir::include_dir!("$CARGO_MANIFEST_DIR/testdata/locales");
+
+        let localizer = Localizer::from_include_dir(&LOCALES, Locale::en_GB).unwrap();
+        let mut locales: Vec<_> = localizer.iter_locales().map(Locale::as_str).collect();
+        locales.sort_unstable();
+        assert_eq!(locales, ["de_DE", "en_GB"]);
+        let catalog = localizer.get_catalog(Locale::de_DE);
+        assert_eq!(catalog.gettext("Yes"), "Ja");
+        assert_eq!(catalog.ngettext("file", "files", 2), "Dateien");
+
+        assert!(matches!(
+            Localizer::from_include_dir(&LOCALES, Locale::fr_FR),
+            Err(MoDirError::MissingFallback(_))
+        ));
+    }
+}
@@ -0,0 +1,538 @@
+//! Additional localized number formats.
+
+use crate::{format_f64, format_int, Locale};
+
+/// How the exponent is rendered by [format_scientific].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ExponentStyle {
+    /// E notation, e.g. `1,23e3`.
+    E,
+    /// Unicode superscript digits, e.g. `1,23×10³`.
+    Superscript,
+}
+
+/// Formats `f` in scientific notation with `precision` digits after the decimal point of the
+/// mantissa according to `locale`.
+///
+/// If necessary the mantissa is rounded to `precision`.
+pub fn format_scientific<N: Into<f64>>(
+    f: N,
+    precision: u8,
+    style: ExponentStyle,
+    locale: Locale,
+) -> String {
+    let f = f.into();
+    let nf_locale: num_format::Locale = locale.into();
+    if !f.is_finite() {
+        return format_non_finite(f, nf_locale);
+    }
+
+    let formatted = format!("{:.*e}", usize::from(precision), f);
+    let (mantissa, exponent) = formatted
+        .split_once('e')
+        .expect("Unreachable: scientific notation without exponent");
+    let exponent: i32 = exponent
+        .parse()
+        .expect("Unreachable: invalid exponent in scientific notation");
+
+    format_mantissa_exponent(mantissa, exponent, style, nf_locale)
+}
+
+/// Formats `f` in engineering notation with an exponent which is a multiple of three and
+/// `precision` digits after the decimal point of the mantissa according to `locale`, e.g.
+/// `12,3×10³` for `12345` in German.
+///
+/// The mantissa is in `1..1000` unless `f` is zero. If necessary it is rounded to `precision`.
+pub fn format_engineering<N: Into<f64>>(f: N, precision: u8, locale: Locale) -> String {
+    let f = f.into();
+    let nf_locale: num_format::Locale = locale.into();
+    if !f.is_finite() {
+        return format_non_finite(f, nf_locale);
+    }
+
+    let precision = usize::from(precision);
+    let mantissa = |exponent: i32| format!("{:.*}", precision, f / 10f64.powi(exponent));
+    let mut exponent = if f == 0.0 {
+        0
+    } else {
+        let formatted = format!("{:e}", f);
+        let (_, exponent) = formatted
+            .split_once('e')
+            .expect("Unreachable: scientific notation without exponent");
+        let exponent: i32 = exponent
+            .parse()
+            .expect("Unreachable: invalid exponent in scientific notation");
+        exponent - exponent.rem_euclid(3)
+    };
+    // Rounding may carry over to a fourth digit before the decimal point, e.g. for `999.96`.
+    if mantissa(exponent)
+        .parse::<f64>()
+        .map_or(false, |mantissa| mantissa.abs() >= 1000.0)
+    {
+        exponent += 3;
+    }
+
+    format_mantissa_exponent(
+        &mantissa(exponent),
+        exponent,
+        ExponentStyle::Superscript,
+        nf_locale,
+    )
+}
+
+/// Formats `f` rounded to `precision` digits after the decimal point according to `locale`.
+///
+/// A negative `precision` rounds to powers of ten, e.g. `-2` rounds to the nearest hundred:
+/// `format_rounded(12345, -2, Locale::de_DE)` returns `12.300`. Halves are rounded away from zero.
+pub fn format_rounded<N: Into<f64>>(f: N, precision: i8, locale: Locale) -> String {
+    let f = f.into();
+    match u8::try_from(precision) {
+        Ok(precision) => format_f64(f, precision, locale),
+        Err(_) => {
+            let factor = 10f64.powi(-i32::from(precision));
+            let rounded = (f / factor).round() * factor;
+            // Avoid formatting `-0` if a small negative number is rounded to zero.
+            format_f64(if rounded == 0.0 { 0.0 } else { rounded }, 0, locale)
+        }
+    }
+}
+
+/// The units of [format_rate].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RateUnit {
+    /// Bytes per second, e.g. `MB/s`.
+    BytesPerSecond,
+    /// Bits per second, e.g. `Mbit/s`.
+    BitsPerSecond,
+}
+
+/// Formats the data rate `value` given in bytes or bits per second according to `locale`.
+///
+/// The value is scaled with SI prefixes (powers of 1000) up to tera, e.g. `1500000` bytes per
+/// second become `1,5 MB/s` in German. Scaled values have one digit after the decimal point, values
+/// below one kilo unit have none. French uses `o` for octets and Russian Cyrillic unit symbols.
+pub fn format_rate(value: f64, unit: RateUnit, locale: Locale) -> String {
+    const PREFIXES: [&str; 5] = ["", "k", "M", "G", "T"];
+    const CYRILLIC_PREFIXES: [&str; 5] = ["", "к", "М", "Г", "Т"];
+
+    // Scale down while the rounded value would have four digits.
+    let precision = |scale: usize| u8::from(scale > 0);
+    let rounded = |value: f64, scale| {
+        let factor = 10f64.powi(precision(scale).into());
+        (value * factor).round() / factor
+    };
+    let mut scale = 0;
+    let mut scaled = value;
+    while scale + 1 < PREFIXES.len() && rounded(scaled, scale).abs() >= 1000.0 {
+        scaled /= 1000.0;
+        scale += 1;
+    }
+
+    let (prefixes, symbol, per_second) = match (locale, unit) {
+        (Locale::ru_RU, RateUnit::BytesPerSecond) => (CYRILLIC_PREFIXES, "Б", "/с"),
+        (Locale::ru_RU, RateUnit::BitsPerSecond) => (CYRILLIC_PREFIXES, "бит", "/с"),
+        (Locale::fr_FR, RateUnit::BytesPerSecond) => (PREFIXES, "o", "/s"),
+        (_, RateUnit::BytesPerSecond) => (PREFIXES, "B", "/s"),
+        (_, RateUnit::BitsPerSecond) => (PREFIXES, "bit", "/s"),
+    };
+
+    format!(
+        "{} {}{}{}",
+        format_f64(scaled, precision(scale), locale),
+        prefixes[scale],
+        symbol,
+        per_second
+    )
+}
+
+/// Formats `value` as a difference in percentage points with `precision` digits after the decimal
+/// point, e.g. `2,5 Pp.` in German or `2.5 pp` in English.
+///
+/// Percentage points are the difference of two percentages, e.g. going from 10 % to 12 % is an
+/// increase by 2 percentage points but by 20 percent.
+pub fn format_percentage_points<N: Into<f64>>(value: N, precision: u8, locale: Locale) -> String {
+    format!(
+        "{} {}",
+        format_f64(value, precision, locale),
+        locale.data().percentage_points
+    )
+}
+
+/// How [format_percent_delta] shows a change of zero.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ZeroSign {
+    /// A plus-minus sign, e.g. `±0,0 %`.
+    PlusMinus,
+    /// No sign, e.g. `0,0 %`.
+    Unsigned,
+}
+
+/// Formats the change `fraction` as percentage with an explicit sign and `precision` digits after
+/// the decimal point, e.g. `+5,0 %` or `−3,2 %` in German and `+5.0%` in English.
+///
+/// Negative changes use the minus sign `−` (U+2212). Changes which round to zero are shown as set
+/// by `zero`. The percent sign is separated from the number as usual in `locale`, e.g. by a
+/// no-break space in German or a narrow no-break space in French.
+pub fn format_percent_delta<N: Into<f64>>(
+    fraction: N,
+    precision: u8,
+    zero: ZeroSign,
+    locale: Locale,
+) -> String {
+    let percent = fraction.into() * 100.0;
+    let formatted = format_f64(percent.abs(), precision, locale);
+
+    let is_zero = !formatted.chars().any(|c| ('1'..='9').contains(&c));
+    let sign = match zero {
+        ZeroSign::PlusMinus if is_zero => "±",
+        ZeroSign::Unsigned if is_zero => "",
+        _ if percent > 0.0 => "+",
+        _ => "\u{2212}",
+    };
+    format!("{}{}{}%", sign, formatted, locale.data().percent_spacing)
+}
+
+/// Formats the count `n` compactly with an abbreviated thousands, millions or billions suffix if
+/// it is at least 10 000, e.g. `12,3 Tsd.` in German or `12.3K` in English, and like
+/// [format_int] below, e.g. `9.999`.
+///
+/// Compact values below 100 have one digit after the decimal point, which is omitted if it is
+/// zero, e.g. `15 Tsd.`. Larger ones have none.
+pub fn format_count_compact(n: u64, locale: Locale) -> String {
+    const THRESHOLD: u64 = 10_000;
+
+    if n < THRESHOLD {
+        return format_int(n, locale);
+    }
+
+    let data = locale.data();
+    let (separator, suffixes) = (data.compact_separator, data.compact_suffixes);
+
+    // Use the next suffix while the rounded value would have four digits.
+    let precision = |value: f64| u8::from(value < 99.95);
+    let mut scale = 0;
+    let mut scaled = n as f64 / 1000.0;
+    while scale + 1 < suffixes.len() && scaled >= 999.5 {
+        scaled /= 1000.0;
+        scale += 1;
+    }
+
+    let formatted = format_f64(scaled, precision(scaled), locale);
+    let zero_decimal = format!("{}0", locale.num_format_locale().decimal());
+    let formatted = formatted.strip_suffix(&zero_decimal).unwrap_or(&formatted);
+    format!("{}{}{}", formatted, separator, suffixes[scale])
+}
+
+/// Formats `values` like [format_f64] and pads them with spaces so that their decimal separators
+/// line up when printed below each other in a monospace column.
+///
+/// All strings have the same number of characters. Values without a decimal separator, like with a
+/// `precision` of `0` or for infinity, are aligned as if it followed them.
+pub fn format_f64_aligned(values: &[f64], precision: u8, locale: Locale) -> Vec<String> {
+    let decimal = locale.num_format_locale().decimal();
+    let formatted: Vec<String> = values
+        .iter()
+        .map(|&value| format_f64(value, precision, locale))
+        .collect();
+    let integer_width = |s: &str| match s.rfind(decimal) {
+        Some(index) => s[..index].chars().count(),
+        None => s.chars().count(),
+    };
+
+    let max_integer_width = formatted.iter().map(|s| integer_width(s)).max();
+    let padded: Vec<String> = formatted
+        .iter()
+        .map(|s| {
+            let padding = max_integer_width.unwrap_or(0) - integer_width(s);
+            format!("{}{}", " ".repeat(padding), s)
+        })
+        .collect();
+    let max_width = padded.iter().map(|s| s.chars().count()).max();
+    padded
+        .into_iter()
+        .map(|s| {
+            let padding = max_width.unwrap_or(0) - s.chars().count();
+            s + &" ".repeat(padding)
+        })
+        .collect()
+}
+
+/// Joins `mantissa` as formatted by Rust and `exponent` according to `style` and `nf_locale`.
+fn format_mantissa_exponent(
+    mantissa: &str,
+    exponent: i32,
+    style: ExponentStyle,
+    nf_locale: num_format::Locale,
+) -> String {
+    let mantissa = mantissa
+        .replace('.', nf_locale.decimal())
+        .replace('-', nf_locale.minus_sign());
+    match style {
+        ExponentStyle::E => format!("{}e{}", mantissa, exponent),
+        ExponentStyle::Superscript => format!("{}×10{}", mantissa, superscript(exponent)),
+    }
+}
+
+/// Formats infinity and NaN according to `nf_locale`.
+fn format_non_finite(f: f64, nf_locale: num_format::Locale) -> String {
+    if f.is_nan() {
+        nf_locale.nan().to_owned()
+    } else if f.is_sign_negative() {
+        format!("{}{}", nf_locale.minus_sign(), nf_locale.infinity())
+    } else {
+        nf_locale.infinity().to_owned()
+    }
+}
+
+/// Renders `n` with Unicode superscript digits.
+fn superscript(n: i32) -> String {
+    n.to_string()
+        .chars()
+        .map(|c| match c {
+            '-' => '⁻',
+            '0' => '⁰',
+            '1' => '¹',
+            '2' => '²',
+            '3' => '³',
+            '4' => '⁴',
+            '5' => '⁵',
+            '6' => '⁶',
+            '7' => '⁷',
+            '8' => '⁸',
+            '9' => '⁹',
+            c => c,
+        })
+        .collect()
+}
+
+/// How [format_rating] renders a rating.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RatingStyle {
+    /// A fraction, e.g. `4,5/5`.
+    Fraction,
+    /// A localized phrase, e.g. `4,5 von 5` in German or `4.5 out of 5` in English.
+    OutOf,
+}
+
+/// Formats the rating `value` out of `max`, e.g. `4,5/5` or `4,5 von 5` in German.
+///
+/// The value has one digit after the decimal point, which is omitted if it is zero, e.g. `4/5`.
+pub fn format_rating(value: f64, max: u8, style: RatingStyle, locale: Locale) -> String {
+    let formatted = format_f64(value, 1, locale);
+    let zero_decimal = format!("{}0", locale.num_format_locale().decimal());
+    let formatted = formatted.strip_suffix(&zero_decimal).unwrap_or(&formatted);
+
+    match style {
+        RatingStyle::Fraction => format!("{}/{}", formatted, max),
+        RatingStyle::OutOf => format!("{} {} {}", formatted, locale.data().out_of, max),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        format_count_compact, format_engineering, format_f64_aligned, format_percent_delta,
+        format_percentage_points, format_rate, format_rating, format_rounded, format_scientific,
+        ExponentStyle, RateUnit, RatingStyle, ZeroSign,
+    };
+    use crate::Locale;
+
+    #[test]
+    fn scientific() {
+        let format =
+            |f: f64, precision, style, locale| format_scientific(f, precision, style, locale);
+
+        assert_eq!(format(1234.0, 2, ExponentStyle::E, Locale::de_DE), "1,23e3");
+        assert_eq!(format(1234.0, 2, ExponentStyle::E, Locale::en_GB), "1.23e3");
+        assert_eq!(
+            format(1234.0, 2, ExponentStyle::Superscript, Locale::de_DE),
+            "1,23×10³"
+        );
+        assert_eq!(
+            format(-0.000123, 1, ExponentStyle::Superscript, Locale::de_DE),
+            "-1,2×10⁻⁴"
+        );
+        assert_eq!(
+            format(0.0, 0, ExponentStyle::Superscript, Locale::en_GB),
+            "0×10⁰"
+        );
+        assert_eq!(
+            format(6.02e23, 2, ExponentStyle::Superscript, Locale::fr_FR),
+            "6,02×10²³"
+        );
+        assert_eq!(
+            format(f64::NEG_INFINITY, 2, ExponentStyle::E, Locale::de_DE),
+            "-∞"
+        );
+    }
+
+    #[test]
+    fn rounded() {
+        assert_eq!(format_rounded(12345, -2, Locale::de_DE), "12.300");
+        assert_eq!(format_rounded(12345, -3, Locale::de_DE), "12.000");
+        assert_eq!(format_rounded(12500, -3, Locale::en_GB), "13,000");
+        assert_eq!(format_rounded(-12500, -3, Locale::en_GB), "-13,000");
+        assert_eq!(format_rounded(-40, -2, Locale::de_DE), "0");
+        assert_eq!(format_rounded(0, -2, Locale::de_DE), "0");
+        assert_eq!(format_rounded(1234.5678, 2, Locale::de_DE), "1.234,57");
+        assert_eq!(format_rounded(1234.5678, 0, Locale::de_DE), "1.235");
+    }
+
+    #[test]
+    fn rate() {
+        let bytes = |value, locale| format_rate(value, RateUnit::BytesPerSecond, locale);
+        let bits = |value, locale| format_rate(value, RateUnit::BitsPerSecond, locale);
+
+        assert_eq!(bytes(999.0, Locale::de_DE), "999 B/s");
+        assert_eq!(bytes(999.6, Locale::de_DE), "1,0 kB/s");
+        assert_eq!(bytes(1000.0, Locale::de_DE), "1,0 kB/s");
+        assert_eq!(bytes(1_500_000.0, Locale::de_DE), "1,5 MB/s");
+        assert_eq!(bytes(1_500_000.0, Locale::en_GB), "1.5 MB/s");
+        assert_eq!(bytes(999_960.0, Locale::en_GB), "1.0 MB/s");
+        assert_eq!(
+            bytes(2_500_000_000_000_000.0, Locale::en_GB),
+            "2,500.0 TB/s"
+        );
+        assert_eq!(bytes(1_500_000.0, Locale::fr_FR), "1,5 Mo/s");
+        assert_eq!(bits(100_000_000.0, Locale::de_DE), "100,0 Mbit/s");
+        assert_eq!(bits(2_000.0, Locale::ru_RU), "2,0 кбит/с");
+    }
+
+    #[test]
+    fn percentage_points() {
+        let format = |value: f64, locale| format_percentage_points(value, 1, locale);
+
+        assert_eq!(format(2.5, Locale::de_DE), "2,5 Pp.");
+        assert_eq!(format(2.5, Locale::en_GB), "2.5 pp");
+        assert_eq!(format(-0.3, Locale::es_ES), "-0,3 p. p.");
+        assert_eq!(format(2.5, Locale::fr_FR), "2,5 pts");
+        assert_eq!(format(2.5, Locale::it_IT), "2,5 p.p.");
+        assert_eq!(format(2.5, Locale::pt_PT), "2,5 p.p.");
+        assert_eq!(format(2.5, Locale::ru_RU), "2,5 п. п.");
+    }
+
+    #[test]
+    fn aligned() {
+        let values = [1234.5, -3.25, 0.126, f64::INFINITY];
+        let aligned = format_f64_aligned(&values, 2, Locale::de_DE);
+        assert_eq!(aligned, ["1.234,50", "   -3,25", "    0,13", "  inf   "]);
+        for s in &aligned[..3] {
+            assert_eq!(s.find(',').unwrap(), 5);
+        }
+
+        assert_eq!(
+            format_f64_aligned(&[10.0, 2000.0], 0, Locale::en_GB),
+            ["   10", "2,000"]
+        );
+        assert!(format_f64_aligned(&[], 2, Locale::en_GB).is_empty());
+    }
+
+    #[test]
+    fn count_compact() {
+        assert_eq!(format_count_compact(9_999, Locale::de_DE), "9.999");
+        assert_eq!(format_count_compact(10_000, Locale::de_DE), "10\u{a0}Tsd.");
+        assert_eq!(
+            format_count_compact(12_345, Locale::de_DE),
+            "12,3\u{a0}Tsd."
+        );
+        assert_eq!(format_count_compact(12_345, Locale::en_GB), "12.3K");
+        assert_eq!(
+            format_count_compact(123_456, Locale::de_DE),
+            "123\u{a0}Tsd."
+        );
+        assert_eq!(format_count_compact(999_999, Locale::de_DE), "1\u{a0}Mio.");
+        assert_eq!(format_count_compact(1_234_567, Locale::en_GB), "1.2M");
+        assert_eq!(
+            format_count_compact(2_345_678_901, Locale::ru_RU),
+            "2,3\u{a0}млрд"
+        );
+        assert_eq!(
+            format_count_compact(4_000_000_000_000, Locale::en_GB),
+            "4,000B"
+        );
+    }
+
+    #[test]
+    fn percent_delta() {
+        let format = |fraction: f64, zero, locale| format_percent_delta(fraction, 1, zero, locale);
+
+        assert_eq!(
+            format(0.05, ZeroSign::PlusMinus, Locale::de_DE),
+            "+5,0\u{a0}%"
+        );
+        assert_eq!(
+            format(-0.032, ZeroSign::PlusMinus, Locale::de_DE),
+            "\u{2212}3,2\u{a0}%"
+        );
+        assert_eq!(
+            format(0.0, ZeroSign::PlusMinus, Locale::de_DE),
+            "±0,0\u{a0}%"
+        );
+        assert_eq!(format(0.0, ZeroSign::Unsigned, Locale::de_DE), "0,0\u{a0}%");
+        assert_eq!(format(0.05, ZeroSign::PlusMinus, Locale::en_GB), "+5.0%");
+        assert_eq!(
+            format(-0.032, ZeroSign::PlusMinus, Locale::en_GB),
+            "\u{2212}3.2%"
+        );
+        assert_eq!(format(-0.0001, ZeroSign::PlusMinus, Locale::en_GB), "±0.0%");
+        assert_eq!(format(0.0, ZeroSign::Unsigned, Locale::en_GB), "0.0%");
+        assert_eq!(
+            format(0.124, ZeroSign::PlusMinus, Locale::fr_FR),
+            "+12,4\u{202f}%"
+        );
+    }
+
+    #[test]
+    fn engineering() {
+        assert_eq!(format_engineering(12345, 1, Locale::de_DE), "12,3×10³");
+        assert_eq!(format_engineering(12345, 2, Locale::en_GB), "12.35×10³");
+        assert_eq!(format_engineering(123456, 0, Locale::de_DE), "123×10³");
+        assert_eq!(format_engineering(1234567, 2, Locale::de_DE), "1,23×10⁶");
+        assert_eq!(format_engineering(0.00042, 1, Locale::de_DE), "420,0×10⁻⁶");
+        assert_eq!(format_engineering(-5, 1, Locale::de_DE), "-5,0×10⁰");
+        assert_eq!(format_engineering(999.96, 1, Locale::de_DE), "1,0×10³");
+        assert_eq!(format_engineering(999.6, 0, Locale::de_DE), "1×10³");
+        assert_eq!(format_engineering(0, 1, Locale::de_DE), "0,0×10⁰");
+    }
+
+    #[test]
+    fn rating() {
+        assert_eq!(
+            format_rating(4.5, 5, RatingStyle::Fraction, Locale::de_DE),
+            "4,5/5"
+        );
+        assert_eq!(
+            format_rating(4.5, 5, RatingStyle::Fraction, Locale::en_GB),
+            "4.5/5"
+        );
+        assert_eq!(
+            format_rating(4.0, 5, RatingStyle::Fraction, Locale::de_DE),
+            "4/5"
+        );
+        assert_eq!(
+            format_rating(7.26, 10, RatingStyle::Fraction, Locale::fr_FR),
+            "7,3/10"
+        );
+
+        assert_eq!(
+            format_rating(4.5, 5, RatingStyle::OutOf, Locale::de_DE),
+            "4,5 von 5"
+        );
+        assert_eq!(
+            format_rating(4.5, 5, RatingStyle::OutOf, Locale::en_GB),
+            "4.5 out of 5"
+        );
+        assert_eq!(
+            format_rating(3.0, 5, RatingStyle::OutOf, Locale::fr_FR),
+            "3 sur 5"
+        );
+        assert_eq!(
+            format_rating(4.5, 5, RatingStyle::OutOf, Locale::ru_RU),
+            "4,5 из 5"
+        );
+        assert_eq!(
+            format_rating(4.5, 5, RatingStyle::OutOf, Locale::it_IT),
+            "4,5 su 5"
+        );
+    }
+}
@@ -0,0 +1,500 @@
+//! Plural rules of the supported locales.
+
+use crate::{format_int, Locale};
+use thiserror::Error;
+
+/// The CLDR plural categories.
+///
+/// See [here](https://cldr.unicode.org/index/cldr-spec/plural-rules) for more information.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PluralCategory {
+    /// Used for zero in some languages.
+    Zero,
+    /// Used for one and sometimes other numbers, e.g. 21 in Russian.
+    One,
+    /// Used for two in some languages.
+    Two,
+    /// Used for small numbers like 2 to 4 in Russian.
+    Few,
+    /// Used for large numbers or e.g. 5 to 20 in Russian.
+    Many,
+    /// Used for all remaining numbers.
+    Other,
+}
+
+impl PluralCategory {
+    /// Returns the CLDR name of the category, e.g. `"one"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+}
+
+impl Locale {
+    /// Selects the CLDR plural category of the [Locale] for the cardinal number `n`.
+    pub fn select_plural(&self, n: u64) -> PluralCategory {
+        // Romance languages use `many` for exact millions, e.g. "1 000 000 de fichiers".
+        let millions = n != 0 && n % 1_000_000 == 0;
+        match self {
+            Locale::de_DE | Locale::en_GB if n == 1 => PluralCategory::One,
+            Locale::de_DE | Locale::en_GB => PluralCategory::Other,
+            Locale::es_ES | Locale::it_IT | Locale::pt_PT if n == 1 => PluralCategory::One,
+            Locale::fr_FR if n <= 1 => PluralCategory::One,
+            Locale::es_ES | Locale::fr_FR | Locale::it_IT | Locale::pt_PT if millions => {
+                PluralCategory::Many
+            }
+            Locale::es_ES | Locale::fr_FR | Locale::it_IT | Locale::pt_PT => PluralCategory::Other,
+            Locale::ru_RU => match (n % 10, n % 100) {
+                (1, rem) if rem != 11 => PluralCategory::One,
+                (2..=4, rem) if !(12..=14).contains(&rem) => PluralCategory::Few,
+                _ => PluralCategory::Many,
+            },
+        }
+    }
+}
+
+/// Replaces all inline plural blocks for `key` in `tpl`, e.g.
+/// `{count, plural, one {# item} other {# items}}`, with the branch selected for `n` in `locale`.
+///
+/// Branches are selected by an exact match like `=0` first, then by the [PluralCategory] and
+/// finally `other`. `#` in the selected branch is replaced with `n` formatted by [format_int].
+/// Returns `None` if `tpl` contains no such block.
+pub(crate) fn expand_plural_blocks(tpl: &str, key: &str, n: u64, locale: Locale) -> Option<String> {
+    let mut expanded = String::with_capacity(tpl.len());
+    let mut rest = tpl;
+    let mut found = false;
+
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        match parse_plural_block(&rest[start..], key) {
+            Some((branches, len)) => {
+                let exact = format!("={}", n);
+                let category = locale.select_plural(n).as_str();
+                let branch = [exact.as_str(), category, "other"]
+                    .iter()
+                    .find_map(|selector| branches.iter().find(|(s, _)| s == selector));
+                match branch {
+                    Some((_, text)) => {
+                        expanded.push_str(&replace_count(text, &format_int(n, locale)));
+                        found = true;
+                    }
+                    None => expanded.push_str(&rest[start..start + len]),
+                }
+                rest = &rest[start + len..];
+            }
+            None => {
+                expanded.push('{');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+
+    found.then_some(expanded)
+}
+
+/// Parses the plural block for `key` at the start of `s`.
+///
+/// Returns the selectors with their branch texts and the length of the block.
+fn parse_plural_block<'s>(s: &'s str, key: &str) -> Option<(Vec<(&'s str, &'s str)>, usize)> {
+    let rest = s.strip_prefix('{')?.trim_start();
+    let rest = rest.strip_prefix(key)?.trim_start();
+    let rest = rest.strip_prefix(',')?.trim_start();
+    let rest = rest.strip_prefix("plural")?.trim_start();
+    let mut rest = rest.strip_prefix(',')?;
+
+    let mut branches = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if let Some(end) = rest.strip_prefix('}') {
+            return Some((branches, s.len() - end.len()));
+        }
+
+        let selector_len = rest.find(|c: char| c.is_whitespace() || c == '{')?;
+        let (selector, after) = rest.split_at(selector_len);
+        let after = after.trim_start().strip_prefix('{')?;
+
+        // Find the closing brace of the branch, skipping nested braces.
+        let mut depth = 0;
+        let end = after.char_indices().find_map(|(i, c)| match c {
+            '{' => {
+                depth += 1;
+                None
+            }
+            '}' if depth == 0 => Some(i),
+            '}' => {
+                depth -= 1;
+                None
+            }
+            _ => None,
+        })?;
+        branches.push((selector, &after[..end]));
+        rest = &after[end + 1..];
+    }
+}
+
+/// Replaces every `#` outside of nested braces in `text` with `count`.
+fn replace_count(text: &str, count: &str) -> String {
+    let mut depth = 0;
+    let mut replaced = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            '#' if depth == 0 => {
+                replaced.push_str(count);
+                continue;
+            }
+            _ => (),
+        }
+        replaced.push(c);
+    }
+    replaced
+}
+
+/// A plural rule parsed from the `Plural-Forms` header of a PO file.
+///
+/// Created with [parse_plural_forms].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PluralRule {
+    nplurals: usize,
+    plural: Expr,
+}
+
+impl PluralRule {
+    /// Returns the number of plural forms.
+    pub fn nplurals(&self) -> usize {
+        self.nplurals
+    }
+
+    /// Selects the index of the plural form for `n`.
+    ///
+    /// Like gettext, the first form is selected if the expression evaluates to an index out of
+    /// range.
+    pub fn select(&self, n: u64) -> usize {
+        match usize::try_from(self.plural.eval(n)) {
+            Ok(index) if index < self.nplurals => index,
+            _ => 0,
+        }
+    }
+}
+
+/// Parses a `Plural-Forms` header value like `nplurals=2; plural=(n != 1);`.
+///
+/// The plural expression supports the C operators used in PO files: `?:`, `||`, `&&`, `==`, `!=`,
+/// `<`, `<=`, `>`, `>=`, `+`, `-`, `*`, `/`, `%`, `!` and parentheses.
+pub fn parse_plural_forms(expr: &str) -> Result<PluralRule, ParsePluralFormsError> {
+    let err = |msg: &str| ParsePluralFormsError(format!("{} in {:?}", msg, expr));
+
+    let mut nplurals = None;
+    let mut plural = None;
+    for part in expr
+        .split(';')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+    {
+        let (key, value) = part.split_once('=').ok_or_else(|| err("missing ="))?;
+        match key.trim() {
+            "nplurals" => {
+                let n = value.trim().parse().map_err(|_| err("invalid nplurals"))?;
+                nplurals = Some(n);
+            }
+            "plural" => {
+                let mut parser = ExprParser {
+                    src: value.as_bytes(),
+                    pos: 0,
+                };
+                let parsed = parser.ternary().ok_or_else(|| err("invalid plural"))?;
+                parser.skip_whitespace();
+                if parser.pos != parser.src.len() {
+                    return Err(err("unexpected trailing characters"));
+                }
+                plural = Some(parsed);
+            }
+            _ => return Err(err("unknown key")),
+        }
+    }
+
+    match (nplurals, plural) {
+        (Some(0), _) => Err(err("nplurals must not be 0")),
+        (Some(nplurals), Some(plural)) => Ok(PluralRule { nplurals, plural }),
+        (None, _) => Err(err("missing nplurals")),
+        (_, None) => Err(err("missing plural")),
+    }
+}
+
+/// Received an invalid `Plural-Forms` header.
+#[derive(Debug, Error, Clone, Eq, PartialEq)]
+#[error("Invalid plural forms: {0}")]
+pub struct ParsePluralFormsError(pub String);
+
+/// A plural expression.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Expr {
+    N,
+    Int(u64),
+    Not(Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    Ternary(Box<Expr>, Box<Expr>, Box<Expr>),
+}
+
+/// The binary operators of plural expressions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl Expr {
+    /// Evaluates the expression like C would with unsigned integers. Division by zero yields 0.
+    fn eval(&self, n: u64) -> u64 {
+        match self {
+            Expr::N => n,
+            Expr::Int(i) => *i,
+            Expr::Not(e) => u64::from(e.eval(n) == 0),
+            Expr::Ternary(cond, then, otherwise) => {
+                if cond.eval(n) != 0 {
+                    then.eval(n)
+                } else {
+                    otherwise.eval(n)
+                }
+            }
+            Expr::Binary(lhs, op, rhs) => {
+                let lhs = lhs.eval(n);
+                // Evaluate lazily to short-circuit like C.
+                let rhs = || rhs.eval(n);
+                match op {
+                    BinaryOp::Or => u64::from(lhs != 0 || rhs() != 0),
+                    BinaryOp::And => u64::from(lhs != 0 && rhs() != 0),
+                    BinaryOp::Eq => u64::from(lhs == rhs()),
+                    BinaryOp::Ne => u64::from(lhs != rhs()),
+                    BinaryOp::Lt => u64::from(lhs < rhs()),
+                    BinaryOp::Le => u64::from(lhs <= rhs()),
+                    BinaryOp::Gt => u64::from(lhs > rhs()),
+                    BinaryOp::Ge => u64::from(lhs >= rhs()),
+                    BinaryOp::Add => lhs.wrapping_add(rhs()),
+                    BinaryOp::Sub => lhs.wrapping_sub(rhs()),
+                    BinaryOp::Mul => lhs.wrapping_mul(rhs()),
+                    BinaryOp::Div => lhs.checked_div(rhs()).unwrap_or(0),
+                    BinaryOp::Rem => lhs.checked_rem(rhs()).unwrap_or(0),
+                }
+            }
+        }
+    }
+}
+
+/// A recursive descent parser for plural expressions, one method per precedence level.
+struct ExprParser<'s> {
+    src: &'s [u8],
+    pos: usize,
+}
+
+impl ExprParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .src
+            .get(self.pos)
+            .map_or(false, u8::is_ascii_whitespace)
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes `token` if it comes next.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.src[self.pos..].starts_with(token.as_bytes()) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ternary(&mut self) -> Option<Expr> {
+        let cond = self.binary(0)?;
+        if !self.eat("?") {
+            return Some(cond);
+        }
+        let then = self.ternary()?;
+        if !self.eat(":") {
+            return None;
+        }
+        let otherwise = self.ternary()?;
+        Some(Expr::Ternary(
+            Box::new(cond),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    /// Parses left associative binary operators starting at precedence `level`.
+    fn binary(&mut self, level: usize) -> Option<Expr> {
+        const LEVELS: &[&[(&str, BinaryOp)]] = &[
+            &[("||", BinaryOp::Or)],
+            &[("&&", BinaryOp::And)],
+            &[("==", BinaryOp::Eq), ("!=", BinaryOp::Ne)],
+            &[
+                ("<=", BinaryOp::Le),
+                (">=", BinaryOp::Ge),
+                ("<", BinaryOp::Lt),
+                (">", BinaryOp::Gt),
+            ],
+            &[("+", BinaryOp::Add), ("-", BinaryOp::Sub)],
+            &[
+                ("*", BinaryOp::Mul),
+                ("/", BinaryOp::Div),
+                ("%", BinaryOp::Rem),
+            ],
+        ];
+        let Some(operators) = LEVELS.get(level) else {
+            return self.unary();
+        };
+
+        let mut lhs = self.binary(level + 1)?;
+        while let Some(&(_, op)) = operators.iter().find(|(token, _)| self.eat(token)) {
+            let rhs = self.binary(level + 1)?;
+            lhs = Expr::Binary(Box::new(lhs), op, Box::new(rhs));
+        }
+        Some(lhs)
+    }
+
+    fn unary(&mut self) -> Option<Expr> {
+        if self.eat("!") {
+            return Some(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let expr = self.ternary()?;
+            return self.eat(")").then_some(expr);
+        }
+        if self.eat("n") {
+            return Some(Expr::N);
+        }
+
+        let start = self.pos;
+        while self.src.get(self.pos).map_or(false, u8::is_ascii_digit) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.src[start..self.pos])
+            .ok()?
+            .parse()
+            .ok()
+            .map(Expr::Int)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_plural_forms, PluralCategory};
+    use crate::{Locale, ToFormat};
+
+    #[test]
+    fn select_plural() {
+        let russian = [
+            (0, PluralCategory::Many),
+            (1, PluralCategory::One),
+            (2, PluralCategory::Few),
+            (5, PluralCategory::Many),
+            (11, PluralCategory::Many),
+            (12, PluralCategory::Many),
+            (21, PluralCategory::One),
+            (22, PluralCategory::Few),
+            (111, PluralCategory::Many),
+        ];
+        for (n, category) in russian {
+            assert_eq!(Locale::ru_RU.select_plural(n), category, "{}", n);
+        }
+        assert_eq!(Locale::de_DE.select_plural(0), PluralCategory::Other);
+        assert_eq!(Locale::de_DE.select_plural(1), PluralCategory::One);
+        assert_eq!(Locale::fr_FR.select_plural(0), PluralCategory::One);
+        assert_eq!(Locale::fr_FR.select_plural(2_000_000), PluralCategory::Many);
+    }
+
+    #[test]
+    fn inline_plural() {
+        let tpl =
+            "{count, plural, =0 {Keine Artikel} one {# Artikel} other {# Artikel}} für {name}";
+        let format = |n| {
+            tpl.to_format()
+                .plural("count", n, Locale::de_DE)
+                .arg("name", &"Anna")
+                .format()
+        };
+        assert_eq!(format(0), "Keine Artikel für Anna");
+        assert_eq!(format(1), "1 Artikel für Anna");
+        assert_eq!(format(1234), "1.234 Artikel für Anna");
+
+        let tpl = "{n, plural, one {# файл} few {# файла} many {# файлов} other {# файла}}";
+        let format = |n| tpl.to_format().plural("n", n, Locale::ru_RU).format();
+        assert_eq!(format(1), "1 файл");
+        assert_eq!(format(3), "3 файла");
+        assert_eq!(format(5), "5 файлов");
+        assert_eq!(format(1021), "1\u{a0}021 файл");
+
+        // Blocks for unknown keys and plain placeholders are left alone.
+        let tpl = "{count} {other, plural, one {#} other {#}}";
+        assert_eq!(
+            tpl.to_format().plural("count", 2, Locale::en_GB).format(),
+            "2 {other, plural, one {#} other {#}}"
+        );
+    }
+
+    #[test]
+    fn plural_forms() {
+        let german = parse_plural_forms("nplurals=2; plural=(n != 1);").unwrap();
+        assert_eq!(german.nplurals(), 2);
+        assert_eq!(german.select(0), 1);
+        assert_eq!(german.select(1), 0);
+        assert_eq!(german.select(2), 1);
+
+        let russian = parse_plural_forms(
+            "nplurals=3; plural=(n%10==1 && n%100!=11 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);",
+        )
+        .unwrap();
+        assert_eq!(russian.nplurals(), 3);
+        for (n, form) in [
+            (1, 0),
+            (2, 1),
+            (4, 1),
+            (5, 2),
+            (11, 2),
+            (12, 2),
+            (21, 0),
+            (22, 1),
+            (111, 2),
+        ] {
+            assert_eq!(russian.select(n), form, "{}", n);
+        }
+
+        // Indices out of range select the first form.
+        assert_eq!(
+            parse_plural_forms("nplurals=2; plural=n;")
+                .unwrap()
+                .select(5),
+            0
+        );
+
+        assert!(parse_plural_forms("nplurals=2;").is_err());
+        assert!(parse_plural_forms("plural=n != 1;").is_err());
+        assert!(parse_plural_forms("nplurals=2; plural=(n != 1;").is_err());
+        assert!(parse_plural_forms("nplurals=2; plural=n ? 1;").is_err());
+        assert!(parse_plural_forms("nplurals=2; plural=n x 1;").is_err());
+    }
+}
@@ -0,0 +1,61 @@
+//! Helpers for the string encoding of PO and POT files.
+
+/// Escapes `s` to be used inside a quoted string of a PO file.
+///
+/// This is the inverse of [unescape_po_string].
+pub fn escape_po_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Resolves the escape sequences of a quoted string from a PO file, e.g. `\n` and `\"`.
+///
+/// `s` must not include the surrounding quotes. Unknown escape sequences resolve to the escaped
+/// character.
+pub fn unescape_po_string(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('a') => unescaped.push('\u{7}'),
+            Some('b') => unescaped.push('\u{8}'),
+            Some('f') => unescaped.push('\u{c}'),
+            Some('v') => unescaped.push('\u{b}'),
+            Some(c) => unescaped.push(c),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_po_string, unescape_po_string};
+
+    #[test]
+    fn escaping() {
+        let s = "Say \"hi\"\n\tto C:\\dir\r";
+        let escaped = r#"Say \"hi\"\n\tto C:\\dir\r"#;
+        assert_eq!(escape_po_string(s), escaped);
+        assert_eq!(unescape_po_string(escaped), s);
+        assert_eq!(unescape_po_string(&escape_po_string(s)), s);
+        assert_eq!(unescape_po_string(r"\q\"), "q\\");
+    }
+}
@@ -0,0 +1,56 @@
+//! Helpers to build gettext catalogs in memory for unit tests.
+//!
+//! ```
+//! use getprose::test_support::plural_catalog;
+//!
+//! let catalog = plural_catalog(
+//!     "nplurals=2; plural=(n != 1);",
+//!     &[("Yes", &["Ja"]), ("{} file", &["{} Datei", "{} Dateien"])],
+//! );
+//! assert_eq!(catalog.gettext("Yes"), "Ja");
+//! assert_eq!(catalog.ngettext("{} file", "{} files", 3), "{} Dateien");
+//! ```
+
+use gettext::Catalog;
+
+/// Builds the bytes of an MO file containing `entries` as msgid/msgstr pairs.
+///
+/// Plural forms are separated by `\0` and a context is separated from the msgid by `\u{4}`. The
+/// header is the msgstr of the empty msgid.
+pub fn mo_file(entries: &[(&str, &str)]) -> Vec<u8> {
+    let entries: Vec<(&[u8], &[u8])> = entries
+        .iter()
+        .map(|(msgid, msgstr)| (msgid.as_bytes(), msgstr.as_bytes()))
+        .collect();
+    crate::mo::write_mo(&entries)
+}
+
+/// Builds a catalog containing `entries` as msgid/msgstr pairs like [mo_file].
+pub fn catalog(entries: &[(&str, &str)]) -> Catalog {
+    Catalog::parse(&mo_file(entries)[..]).expect("failed to parse test catalog")
+}
+
+/// Builds a catalog with the `Plural-Forms` header `plural_forms`, e.g.
+/// `"nplurals=2; plural=(n != 1);"`, containing `entries` as pairs of msgid and the translated
+/// forms.
+///
+/// Entries with a single form are singular translations.
+///
+/// # Panics
+///
+/// Panics if `plural_forms` cannot be parsed by gettext.
+pub fn plural_catalog(plural_forms: &str, entries: &[(&str, &[&str])]) -> Catalog {
+    let header = format!(
+        "Content-Type: text/plain; charset=UTF-8\nPlural-Forms: {}\n",
+        plural_forms
+    );
+    let msgstrs: Vec<String> = entries.iter().map(|(_, forms)| forms.join("\0")).collect();
+    let mut mo_entries = vec![("", header.as_str())];
+    mo_entries.extend(
+        entries
+            .iter()
+            .zip(&msgstrs)
+            .map(|((msgid, _), msgstr)| (*msgid, msgstr.as_str())),
+    );
+    catalog(&mo_entries)
+}
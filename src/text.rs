@@ -0,0 +1,381 @@
+//! Locale-specific text transformations.
+
+use crate::{format_int, Locale, PluralCategory};
+
+impl Locale {
+    /// Wraps `s` in the primary quotation marks of the [Locale], e.g. `„s“` in German.
+    ///
+    /// French guillemets are separated from `s` by no-break spaces.
+    pub fn quote(&self, s: &str) -> String {
+        let (start, end) = match self {
+            Locale::de_DE => ("„", "“"),
+            Locale::en_GB => ("“", "”"),
+            Locale::es_ES | Locale::it_IT | Locale::pt_PT | Locale::ru_RU => ("«", "»"),
+            Locale::fr_FR => ("«\u{a0}", "\u{a0}»"),
+        };
+        format!("{}{}{}", start, s, end)
+    }
+
+    /// Wraps `s` in the secondary quotation marks of the [Locale] used for quotes within quotes,
+    /// e.g. `‚s‘` in German.
+    pub fn quote_secondary(&self, s: &str) -> String {
+        let (start, end) = match self {
+            Locale::de_DE => ("‚", "‘"),
+            Locale::en_GB => ("‘", "’"),
+            Locale::es_ES | Locale::fr_FR | Locale::it_IT | Locale::pt_PT => ("“", "”"),
+            Locale::ru_RU => ("„", "“"),
+        };
+        format!("{}{}{}", start, s, end)
+    }
+
+    /// Replaces the spaces between numbers and following units in `s` with no-break spaces to
+    /// keep them on the same line, e.g. `"5 kg"` becomes `"5\u{a0}kg"`.
+    ///
+    /// A unit is anything starting with a letter or one of the symbols `%‰°€$£¥₽µ`. French uses a
+    /// narrow no-break space before `%` and `‰`.
+    pub fn protect_units(&self, s: &str) -> String {
+        let mut protected = String::with_capacity(s.len());
+        let mut chars = s.chars().peekable();
+        let mut after_digit = false;
+
+        while let Some(c) = chars.next() {
+            let next = chars.peek().copied();
+            match next {
+                Some(unit) if c == ' ' && after_digit && is_unit_start(unit) => {
+                    protected.push(match (self, unit) {
+                        (Locale::fr_FR, '%' | '‰') => '\u{202f}',
+                        _ => '\u{a0}',
+                    });
+                }
+                _ => protected.push(c),
+            }
+            after_digit = c.is_ascii_digit();
+        }
+
+        protected
+    }
+
+    /// Uppercases the first letter of `s` for sentence case and leaves the rest untouched, e.g.
+    /// `"élan vital"` becomes `"Élan vital"`.
+    ///
+    /// Combining marks following the first letter, like in a decomposed `"e\u{301}"`, are kept with
+    /// it. Letters uppercasing to several letters use the first one in upper and the following
+    /// ones in lower case, e.g. `"ﬁ"` becomes `"Fi"`. None of the supported locales need casing
+    /// rules beyond the Unicode defaults.
+    pub fn capitalize_first(&self, s: &str) -> String {
+        let mut chars = s.chars();
+        let Some(first) = chars.next() else {
+            return String::new();
+        };
+
+        let mut capitalized = String::with_capacity(s.len());
+        for (i, c) in first.to_uppercase().enumerate() {
+            if i == 0 {
+                capitalized.push(c);
+            } else {
+                capitalized.extend(c.to_lowercase());
+            }
+        }
+        capitalized.push_str(chars.as_str());
+        capitalized
+    }
+
+    /// Converts `s` to title case for headings, e.g. `"the lord of the rings"` becomes
+    /// `"The Lord of the Rings"` in English.
+    ///
+    /// English capitalizes all words but short articles, conjunctions and prepositions like `a`,
+    /// `and` or `of`, which are lowercased unless they are the first or last word. The other
+    /// locales capitalize nouns by grammar or use sentence case for headings, so only the first
+    /// word is capitalized. The rest of each word is left untouched, e.g. for acronyms.
+    pub fn to_title_case(&self, s: &str) -> String {
+        if *self != Locale::en_GB {
+            return self.capitalize_first(s);
+        }
+
+        let words: Vec<&str> = s.split(' ').collect();
+        let last = words.len() - 1;
+        words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| {
+                let lowercase = word.to_lowercase();
+                if i != 0 && i != last && ENGLISH_TITLE_STOP_WORDS.contains(&lowercase.as_str()) {
+                    lowercase
+                } else {
+                    self.capitalize_first(word)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Words which are not capitalized in English titles unless they are the first or last word.
+const ENGLISH_TITLE_STOP_WORDS: [&str; 22] = [
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into", "nor", "of", "on",
+    "onto", "or", "over", "per", "the", "to", "via", "with",
+];
+
+/// Formats `items` as a list joined with the conjunction of `locale`, showing at most `max_shown`
+/// items followed by the number of omitted items, e.g. `"a, b, c and 5 more"` in English or
+/// `"a, b, c und 5 weitere"` in German.
+///
+/// Lists with at most `max_shown` items are formatted in full, e.g. `"a, b and c"`.
+pub fn format_list_truncated(items: &[&str], max_shown: usize, locale: Locale) -> String {
+    let (more_one, more_other) = match locale {
+        Locale::de_DE => ("{} weiteres", "{} weitere"),
+        Locale::en_GB => ("{} more", "{} more"),
+        Locale::es_ES => ("{} más", "{} más"),
+        Locale::fr_FR => ("{} autre", "{} autres"),
+        Locale::it_IT => ("{} altro", "altri {}"),
+        Locale::pt_PT => ("mais {}", "mais {}"),
+        Locale::ru_RU => ("ещё {}", "ещё {}"),
+    };
+
+    if items.len() <= max_shown {
+        return format_list(items, locale);
+    }
+
+    let remaining = (items.len() - max_shown) as u64;
+    let more = match locale.select_plural(remaining) {
+        PluralCategory::One => more_one,
+        _ => more_other,
+    };
+    let more = more.replace("{}", &format_int(remaining, locale));
+    match &items[..max_shown] {
+        [] => more,
+        shown => format!("{} {} {}", shown.join(", "), conjunction(locale), more),
+    }
+}
+
+/// Formats each count of `counts` with the singular or plural word following it and joins them
+/// like a list, e.g. `"1 Datei und 3 Ordner"` in German.
+///
+/// Each entry is a pair of the count and its singular and plural word. The singular is used for
+/// counts in the [PluralCategory::One] category, the plural for all others, so languages with
+/// more plural forms like Russian should pass the form used for most counts.
+pub fn format_counts(counts: &[(u64, &str, &str)], locale: Locale) -> String {
+    let items: Vec<String> = counts
+        .iter()
+        .map(|&(n, singular, plural)| {
+            let word = match locale.select_plural(n) {
+                PluralCategory::One => singular,
+                _ => plural,
+            };
+            format!("{} {}", format_int(n, locale), word)
+        })
+        .collect();
+    let items: Vec<&str> = items.iter().map(String::as_str).collect();
+    format_list(&items, locale)
+}
+
+/// Joins `items` with commas and the conjunction of `locale` before the last item.
+fn format_list(items: &[&str], locale: Locale) -> String {
+    match items.split_last() {
+        Some((last, [])) => (*last).to_owned(),
+        Some((last, rest)) => format!("{} {} {}", rest.join(", "), conjunction(locale), last),
+        None => String::new(),
+    }
+}
+
+/// Returns the word joining the last two items of a list in `locale`, e.g. "und".
+fn conjunction(locale: Locale) -> &'static str {
+    match locale {
+        Locale::de_DE => "und",
+        Locale::en_GB => "and",
+        Locale::es_ES => "y",
+        Locale::fr_FR => "et",
+        Locale::it_IT | Locale::pt_PT => "e",
+        Locale::ru_RU => "и",
+    }
+}
+
+/// A setting which is switched on or off or chosen automatically, see [format_tristate].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Tristate {
+    /// The setting is switched on.
+    On,
+    /// The setting is switched off.
+    Off,
+    /// The setting is chosen automatically.
+    Auto,
+}
+
+/// Returns the label of `value` for settings in `locale`, e.g. `"Ein"`, `"Aus"` or `"Auto"` in
+/// German.
+pub fn format_tristate(value: Tristate, locale: Locale) -> &'static str {
+    let [on, off, auto] = match locale {
+        Locale::de_DE => ["Ein", "Aus", "Auto"],
+        Locale::en_GB => ["On", "Off", "Auto"],
+        Locale::es_ES => ["Activado", "Desactivado", "Automático"],
+        Locale::fr_FR => ["Activé", "Désactivé", "Auto"],
+        Locale::it_IT => ["Attivo", "Disattivo", "Automatico"],
+        Locale::pt_PT => ["Ligado", "Desligado", "Automático"],
+        Locale::ru_RU => ["Вкл.", "Выкл.", "Авто"],
+    };
+    match value {
+        Tristate::On => on,
+        Tristate::Off => off,
+        Tristate::Auto => auto,
+    }
+}
+
+/// Checks whether a unit may start with `c`.
+fn is_unit_start(c: char) -> bool {
+    c.is_alphabetic() || "%‰°€$£¥₽µ".contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_counts, format_list_truncated, format_tristate, Tristate};
+    use crate::Locale;
+
+    #[test]
+    fn quote() {
+        assert_eq!(Locale::de_DE.quote("Hallo"), "„Hallo“");
+        assert_eq!(Locale::de_DE.quote_secondary("Hallo"), "‚Hallo‘");
+        assert_eq!(Locale::en_GB.quote("Hello"), "“Hello”");
+        assert_eq!(Locale::en_GB.quote_secondary("Hello"), "‘Hello’");
+        assert_eq!(Locale::fr_FR.quote("Bonjour"), "«\u{a0}Bonjour\u{a0}»");
+        assert_eq!(Locale::ru_RU.quote("Привет"), "«Привет»");
+        assert_eq!(
+            Locale::de_DE.quote(&format!(
+                "Er sagte {}",
+                Locale::de_DE.quote_secondary("Hallo")
+            )),
+            "„Er sagte ‚Hallo‘“"
+        );
+    }
+
+    #[test]
+    fn protect_units() {
+        assert_eq!(Locale::de_DE.protect_units("5 kg"), "5\u{a0}kg");
+        assert_eq!(
+            Locale::de_DE.protect_units("Es sind 10 € und 3 % Rabatt, Stand 2024 ."),
+            "Es sind 10\u{a0}€ und 3\u{a0}% Rabatt, Stand 2024 ."
+        );
+        assert_eq!(Locale::fr_FR.protect_units("5 %"), "5\u{202f}%");
+        assert_eq!(Locale::fr_FR.protect_units("5 km"), "5\u{a0}km");
+        assert_eq!(Locale::en_GB.protect_units("Room 5  m"), "Room 5  m");
+    }
+
+    #[test]
+    fn capitalize_first() {
+        assert_eq!(Locale::fr_FR.capitalize_first("élan vital"), "Élan vital");
+        assert_eq!(
+            Locale::fr_FR.capitalize_first("e\u{301}cole"),
+            "E\u{301}cole"
+        );
+        assert_eq!(Locale::de_DE.capitalize_first("über Alles"), "Über Alles");
+        assert_eq!(Locale::ru_RU.capitalize_first("ёлка"), "Ёлка");
+        assert_eq!(Locale::en_GB.capitalize_first("ﬁne"), "Fine");
+        assert_eq!(
+            Locale::en_GB.capitalize_first("already Capital"),
+            "Already Capital"
+        );
+        assert_eq!(Locale::en_GB.capitalize_first(""), "");
+    }
+
+    #[test]
+    fn title_case() {
+        assert_eq!(
+            Locale::en_GB.to_title_case("the lord of the rings"),
+            "The Lord of the Rings"
+        );
+        assert_eq!(
+            Locale::en_GB.to_title_case("a tale OF two cities"),
+            "A Tale of Two Cities"
+        );
+        assert_eq!(
+            Locale::en_GB.to_title_case("what dreams are made of"),
+            "What Dreams Are Made Of"
+        );
+        assert_eq!(
+            Locale::en_GB.to_title_case("using HTML and CSS"),
+            "Using HTML and CSS"
+        );
+        assert_eq!(Locale::en_GB.to_title_case(""), "");
+        assert_eq!(
+            Locale::de_DE.to_title_case("der Herr der Ringe"),
+            "Der Herr der Ringe"
+        );
+        assert_eq!(
+            Locale::fr_FR.to_title_case("le seigneur des anneaux"),
+            "Le seigneur des anneaux"
+        );
+    }
+
+    #[test]
+    fn list_truncated() {
+        let items = ["a", "b", "c", "d", "e"];
+
+        assert_eq!(
+            format_list_truncated(&items, 3, Locale::en_GB),
+            "a, b, c and 2 more"
+        );
+        assert_eq!(
+            format_list_truncated(&items, 3, Locale::de_DE),
+            "a, b, c und 2 weitere"
+        );
+        assert_eq!(
+            format_list_truncated(&items, 4, Locale::de_DE),
+            "a, b, c, d und 1 weiteres"
+        );
+        assert_eq!(
+            format_list_truncated(&items, 4, Locale::it_IT),
+            "a, b, c, d e 1 altro"
+        );
+        assert_eq!(
+            format_list_truncated(&items, 3, Locale::it_IT),
+            "a, b, c e altri 2"
+        );
+        assert_eq!(
+            format_list_truncated(&items, 5, Locale::en_GB),
+            "a, b, c, d and e"
+        );
+        assert_eq!(
+            format_list_truncated(&items, 9, Locale::fr_FR),
+            "a, b, c, d et e"
+        );
+        assert_eq!(format_list_truncated(&items, 0, Locale::en_GB), "5 more");
+        assert_eq!(format_list_truncated(&["a"], 1, Locale::en_GB), "a");
+        assert_eq!(format_list_truncated(&[], 1, Locale::en_GB), "");
+    }
+
+    #[test]
+    fn tristate() {
+        assert_eq!(format_tristate(Tristate::On, Locale::de_DE), "Ein");
+        assert_eq!(format_tristate(Tristate::Off, Locale::de_DE), "Aus");
+        assert_eq!(format_tristate(Tristate::Auto, Locale::de_DE), "Auto");
+        assert_eq!(format_tristate(Tristate::On, Locale::en_GB), "On");
+        assert_eq!(format_tristate(Tristate::Off, Locale::en_GB), "Off");
+        assert_eq!(format_tristate(Tristate::Auto, Locale::en_GB), "Auto");
+    }
+
+    #[test]
+    fn counts() {
+        assert_eq!(
+            format_counts(
+                &[(1, "Datei", "Dateien"), (3, "Ordner", "Ordner")],
+                Locale::de_DE
+            ),
+            "1 Datei und 3 Ordner"
+        );
+        assert_eq!(
+            format_counts(
+                &[
+                    (2, "Datei", "Dateien"),
+                    (1, "Ordner", "Ordner"),
+                    (1500, "Link", "Links")
+                ],
+                Locale::de_DE
+            ),
+            "2 Dateien, 1 Ordner und 1.500 Links"
+        );
+        assert_eq!(
+            format_counts(&[(0, "file", "files")], Locale::en_GB),
+            "0 files"
+        );
+        assert_eq!(format_counts(&[], Locale::en_GB), "");
+    }
+}
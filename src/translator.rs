@@ -0,0 +1,255 @@
+//! Translation bound to a single locale.
+
+use crate::{format_int, CatalogLike, Locale, Localizer, ToFormat};
+use gettext::Catalog;
+use std::collections::HashMap;
+
+/// Translates strings for a single [Locale] without passing the [Localizer] and the locale around.
+///
+/// Created with [Localizer::translator]. The catalog is resolved once on creation, so the fallback
+/// logic of [Localizer::get_catalog] applies.
+#[derive(Debug)]
+pub struct Translator<'a, C = Catalog> {
+    catalog: &'a C,
+    locale: Locale,
+    /// Context used by [Translator::tr] and [Translator::ntr] if set.
+    context: Option<&'a str>,
+}
+
+impl<C> Clone for Translator<'_, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C> Copy for Translator<'_, C> {}
+
+impl<'a, C> Translator<'a, C> {
+    /// Returns the locale the `Translator` was created for.
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// Returns a copy of the `Translator` which translates in `context` by default.
+    ///
+    /// [Translator::tr] and [Translator::ntr] then behave like [Translator::ptr] and
+    /// [Translator::nptr] with `context`, e.g. to avoid msgid collisions between modules. The
+    /// context can still be overridden per call with [Translator::ptr] and [Translator::nptr].
+    pub fn with_default_context(self, context: &'a str) -> Self {
+        Self {
+            context: Some(context),
+            ..self
+        }
+    }
+}
+
+/// The methods of a [Translator] do not look up an empty msgid, since catalogs map it to their
+/// header. Singular messages are returned as the empty string and plural messages as the
+/// untranslated form selected by `n`.
+impl<C: CatalogLike> Translator<'_, C> {
+    /// Translates a singular string in the default context if set, see [CatalogLike::gettext].
+    pub fn tr<'s>(&'s self, msgid: &'s str) -> &'s str {
+        if msgid.is_empty() {
+            return "";
+        }
+        match self.context {
+            Some(context) => self.catalog.pgettext(context, msgid),
+            None => self.catalog.gettext(msgid),
+        }
+    }
+
+    /// Translates a string depending on `n` in the default context if set, see
+    /// [CatalogLike::ngettext].
+    pub fn ntr<'s>(&'s self, msgid: &'s str, msgid_plural: &'s str, n: u64) -> &'s str {
+        if msgid.is_empty() {
+            return untranslated_plural(msgid, msgid_plural, n);
+        }
+        match self.context {
+            Some(context) => self.catalog.npgettext(context, msgid, msgid_plural, n),
+            None => self.catalog.ngettext(msgid, msgid_plural, n),
+        }
+    }
+
+    /// Translates a string depending on `n` like [Translator::ntr] and fills its `{count}`
+    /// placeholder with `n` formatted by [format_int], e.g. `"1.500 Dateien"`.
+    ///
+    /// This is the common case of a pluralized message with a single number. Use
+    /// [FormatBuilder](crate::FormatBuilder) for templates with more arguments.
+    pub fn ntr_simple(&self, msgid: &str, msgid_plural: &str, n: u64) -> String {
+        self.ntr(msgid, msgid_plural, n)
+            .to_format()
+            .arg("count", &format_int(n, self.locale))
+            .format()
+    }
+
+    /// Translates a singular string in `context`, see [CatalogLike::pgettext].
+    pub fn ptr<'s>(&'s self, context: &'s str, msgid: &'s str) -> &'s str {
+        if msgid.is_empty() {
+            return "";
+        }
+        self.catalog.pgettext(context, msgid)
+    }
+
+    /// Translates a string depending on `n` in `context`, see [CatalogLike::npgettext].
+    pub fn nptr<'s>(
+        &'s self,
+        context: &'s str,
+        msgid: &'s str,
+        msgid_plural: &'s str,
+        n: u64,
+    ) -> &'s str {
+        if msgid.is_empty() {
+            return untranslated_plural(msgid, msgid_plural, n);
+        }
+        self.catalog.npgettext(context, msgid, msgid_plural, n)
+    }
+
+    /// Translates all `msgids` like [Translator::tr] and returns the translations by msgid.
+    pub fn tr_many<'k>(&self, msgids: &[&'k str]) -> HashMap<&'k str, String> {
+        msgids
+            .iter()
+            .map(|&msgid| (msgid, self.tr(msgid).to_owned()))
+            .collect()
+    }
+}
+
+/// Selects the untranslated form of a plural message by `n` like gettext does for missing
+/// translations.
+fn untranslated_plural<'s>(msgid: &'s str, msgid_plural: &'s str, n: u64) -> &'s str {
+    if n == 1 {
+        msgid
+    } else {
+        msgid_plural
+    }
+}
+
+impl<C> Localizer<C> {
+    /// Creates a [Translator] for `locale`.
+    pub fn translator(&self, locale: impl Into<Locale>) -> Translator<'_, C> {
+        let locale = locale.into();
+        Translator {
+            catalog: self.get_catalog(locale),
+            locale,
+            context: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::catalog;
+    use crate::{Locale, Localizer};
+    use std::collections::HashMap;
+
+    #[test]
+    fn translator() {
+        let catalogs = HashMap::from([
+            (
+                Locale::de_DE,
+                catalog(&[
+                    ("Yes", "Ja"),
+                    ("file\0files", "Datei\0Dateien"),
+                    ("answer\u{4}No", "Nein"),
+                    ("menu\u{4}Open\0Open all", "Öffnen\0Alle öffnen"),
+                ]),
+            ),
+            (Locale::en_GB, catalog(&[])),
+        ]);
+        let localizer = Localizer::new(catalogs, Locale::en_GB).unwrap();
+
+        let translator = localizer.translator(Locale::de_DE);
+        assert_eq!(translator.locale(), Locale::de_DE);
+        assert_eq!(translator.tr("Yes"), "Ja");
+        assert_eq!(translator.tr("Maybe"), "Maybe");
+        assert_eq!(translator.ntr("file", "files", 1), "Datei");
+        assert_eq!(translator.ntr("file", "files", 2), "Dateien");
+        assert_eq!(translator.ptr("answer", "No"), "Nein");
+        assert_eq!(translator.ptr("other", "No"), "No");
+        assert_eq!(translator.nptr("menu", "Open", "Open all", 1), "Öffnen");
+        assert_eq!(
+            translator.nptr("menu", "Open", "Open all", 5),
+            "Alle öffnen"
+        );
+
+        let translations = translator.tr_many(&["Yes", "Maybe"]);
+        assert_eq!(translations.len(), 2);
+        assert_eq!(translations["Yes"], "Ja");
+        assert_eq!(translations["Maybe"], "Maybe");
+
+        // Unloaded locales use the fallback.
+        let translator = localizer.translator(Locale::fr_FR);
+        assert_eq!(translator.tr("Yes"), "Yes");
+    }
+
+    #[test]
+    fn ntr_simple() {
+        let catalogs = HashMap::from([(
+            Locale::de_DE,
+            catalog(&[(
+                "{count} file\0{count} files",
+                "{count} Datei\0{count} Dateien",
+            )]),
+        )]);
+        let localizer = Localizer::new(catalogs, Locale::de_DE).unwrap();
+        let translator = localizer.translator(Locale::de_DE);
+
+        assert_eq!(
+            translator.ntr_simple("{count} file", "{count} files", 1),
+            "1 Datei"
+        );
+        assert_eq!(
+            translator.ntr_simple("{count} file", "{count} files", 1500),
+            "1.500 Dateien"
+        );
+        assert_eq!(
+            translator.ntr_simple("{count} day", "{count} days", 2),
+            "2 days"
+        );
+    }
+
+    #[test]
+    fn empty_msgid() {
+        let catalogs = HashMap::from([(
+            Locale::de_DE,
+            catalog(&[("", "Content-Type: text/plain; charset=UTF-8\n")]),
+        )]);
+        let localizer = Localizer::new(catalogs, Locale::de_DE).unwrap();
+        let translator = localizer.translator(Locale::de_DE);
+
+        assert_ne!(localizer.get_catalog(Locale::de_DE).gettext(""), "");
+        assert_eq!(translator.tr(""), "");
+        assert_eq!(translator.ntr("", "", 2), "");
+        assert_eq!(translator.ntr("", "{count} items", 1), "");
+        assert_eq!(translator.ntr("", "{count} items", 5), "{count} items");
+        assert_eq!(
+            translator.nptr("menu", "", "{count} items", 5),
+            "{count} items"
+        );
+        assert_eq!(translator.ntr_simple("", "{count} items", 5), "5 items");
+        assert_eq!(translator.ptr("menu", ""), "");
+        assert_eq!(translator.nptr("menu", "", "", 1), "");
+        assert_eq!(translator.with_default_context("menu").tr(""), "");
+        assert_eq!(translator.tr_many(&[""])[""], "");
+    }
+
+    #[test]
+    fn default_context() {
+        let catalogs = HashMap::from([(
+            Locale::de_DE,
+            catalog(&[
+                ("Name", "Name"),
+                ("person\u{4}Name", "Vorname"),
+                ("file\u{4}Name", "Dateiname"),
+                ("person\u{4}child\0children", "Kind\0Kinder"),
+            ]),
+        )]);
+        let localizer = Localizer::new(catalogs, Locale::de_DE).unwrap();
+        let translator = localizer.translator(Locale::de_DE);
+        let person = translator.with_default_context("person");
+
+        assert_eq!(translator.tr("Name"), "Name");
+        assert_eq!(person.tr("Name"), "Vorname");
+        assert_eq!(person.ntr("child", "children", 2), "Kinder");
+        assert_eq!(person.ptr("file", "Name"), "Dateiname");
+    }
+}
@@ -0,0 +1,132 @@
+//! Reloading of catalogs when the MO files in a directory change.
+
+use crate::mo::mo_locale;
+use crate::{Locale, Localizer, MoDirError};
+use gettext::Catalog;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use thiserror::Error;
+
+impl Localizer<Catalog> {
+    /// Loads the catalogs in `dir` like [Localizer::from_mo_dir] and reloads them whenever an MO
+    /// file in `dir` is created, changed or removed.
+    ///
+    /// The catalogs are read through the returned [WatchHandle], which stops watching when it is
+    /// dropped. If reloading fails, e.g. because a file is only partially written, the previous
+    /// catalogs are kept until the next change.
+    pub fn watch_mo_dir(
+        dir: impl AsRef<Path>,
+        fallback: Locale,
+    ) -> Result<WatchHandle, WatchError> {
+        let dir = dir.as_ref().to_owned();
+        let current = Arc::new(RwLock::new(Arc::new(Self::from_mo_dir(&dir, fallback)?)));
+
+        let shared = Arc::clone(&current);
+        let reload_dir = dir.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+                let changes_mo = matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                ) && event.paths.iter().any(|path| mo_locale(path).is_some());
+                if changes_mo {
+                    if let Ok(localizer) = Self::from_mo_dir(&reload_dir, fallback) {
+                        *shared.write().unwrap_or_else(|err| err.into_inner()) =
+                            Arc::new(localizer);
+                    }
+                }
+            })?;
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+        Ok(WatchHandle {
+            current,
+            dir,
+            _watcher: watcher,
+        })
+    }
+}
+
+/// Gives access to the catalogs loaded by [Localizer::watch_mo_dir] and stops watching their
+/// directory when dropped.
+pub struct WatchHandle {
+    /// The latest catalogs, swapped as a whole on reload.
+    current: Arc<RwLock<Arc<Localizer>>>,
+    dir: PathBuf,
+    _watcher: RecommendedWatcher,
+}
+
+impl WatchHandle {
+    /// Returns the latest loaded catalogs.
+    ///
+    /// The returned `Localizer` is not changed by later reloads, so it can be used for a whole
+    /// request while a reload happens concurrently. Call this again to see reloaded catalogs.
+    pub fn localizer(&self) -> Arc<Localizer> {
+        Arc::clone(&self.current.read().unwrap_or_else(|err| err.into_inner()))
+    }
+
+    /// Returns the watched directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl std::fmt::Debug for WatchHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WatchHandle")
+            .field("dir", &self.dir)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Describes why [Localizer::watch_mo_dir] failed.
+#[derive(Debug, Error)]
+pub enum WatchError {
+    /// The catalogs could not be loaded initially.
+    #[error(transparent)]
+    Load(#[from] MoDirError),
+    /// The directory could not be watched.
+    #[error("Could not watch the MO directory: {0}")]
+    Notify(#[from] notify::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_support::mo_file;
+    use crate::{Locale, Localizer};
+    use std::time::{Duration, Instant};
+    use std::{env, fs, process, thread};
+
+    #[test]
+    fn reload() {
+        let dir = env::temp_dir().join(format!("getprose-watch-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("de_DE.mo");
+        fs::write(&path, mo_file(&[("Yes", "Ja")])).unwrap();
+
+        let handle = Localizer::watch_mo_dir(&dir, Locale::de_DE).unwrap();
+        let before = handle.localizer();
+        fs::write(&path, mo_file(&[("Yes", "Jawohl")])).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let reloaded = loop {
+            let localizer = handle.localizer();
+            if localizer.get_catalog(Locale::de_DE).gettext("Yes") == "Jawohl" {
+                break true;
+            }
+            if Instant::now() > deadline {
+                break false;
+            }
+            thread::sleep(Duration::from_millis(20));
+        };
+        drop(handle);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(reloaded, "the catalog was not reloaded");
+        // Localizers obtained before the reload keep their catalogs.
+        assert_eq!(before.get_catalog(Locale::de_DE).gettext("Yes"), "Ja");
+    }
+}